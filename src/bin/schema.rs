@@ -1,10 +1,11 @@
 use cosmwasm_schema::write_api;
-use cw_exam::msg::{ExecMsg, InstantiateMsg, QueryMsg};
+use cw_exam::msg::{ExecMsg, InstantiateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecMsg,
         query: QueryMsg,
+        sudo: SudoMsg,
     }
 }
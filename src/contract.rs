@@ -1,52 +1,368 @@
-use cosmwasm_std::{Coin, DepsMut, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    Addr, Api, BlockInfo, Coin, Decimal, DepsMut, Empty, Env, MessageInfo, Reply, Response,
+    StdResult, Storage, Uint128,
+};
 use cw2::set_contract_version;
 
 use crate::{
-    msg::InstantiateMsg,
-    state::{State, OWNER, STATE},
+    error::ContractError,
+    msg::{AuctionStatus, CommissionSchedule, InstantiateMsg, Role},
+    state::{
+        CollectedFees, Config, EscrowTerms, State, ALLOWLIST, BIDDER_COUNT, CANCELLED, CLOSED,
+        COLLECTED_FEES, CONFIG, CREDITS, ESCROW, HIGHEST_BID, NEXT_PAYOUT_ID, OWNER, PAUSED,
+        PAYOUTS, ROLES, SETTLEMENT,
+    },
 };
 
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-const HIGHEST_BID_KEY: &str = "highest_bid";
-const WINNER_KEY: &str = "winner";
 
-const COMMISSION: u128 = 10;
+const COMMISSION: u64 = 10;
 
 const ATOM: &str = "atom";
 
-pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+const HOOK_REPLY_ID: u64 = 1;
+/// Gas every hook can use, so running out of it fails the hook and not the transaction
+const HOOK_GAS_LIMIT: u64 = 500_000;
+/// Payouts reply with increasing ids starting from this one
+const PAYOUT_REPLY_ID: u64 = 2;
+
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    STATE.save(
+    let owner = match msg.owner {
+        Some(owner) => validate_address(deps.api, &owner)?,
+        None => info.sender.clone(),
+    };
+
+    let eligibility_registry = msg
+        .eligibility_registry
+        .map(|registry| validate_address(deps.api, &registry))
+        .transpose()?;
+
+    let escrow = msg
+        .escrow
+        .map(|escrow| -> Result<_, ContractError> {
+            Ok(EscrowTerms {
+                arbiter: validate_address(deps.api, &escrow.arbiter)?,
+                timeout: escrow.timeout,
+            })
+        })
+        .transpose()?;
+
+    let guardian = msg
+        .guardian
+        .map(|guardian| validate_address(deps.api, &guardian))
+        .transpose()?;
+
+    let denom = msg.denom.unwrap_or_else(|| ATOM.to_string());
+    if denom.is_empty() {
+        return Err(ContractError::InvalidDenom {});
+    }
+
+    let commission = msg.commission.unwrap_or(Decimal::percent(COMMISSION));
+    if commission > Decimal::one() {
+        return Err(ContractError::InvalidCommission {});
+    }
+
+    // A zero coin can't be sent, so nobody could ever register
+    if msg.bond.as_ref().is_some_and(|bond| bond.amount.is_zero()) {
+        return Err(ContractError::InvalidBond {});
+    }
+
+    let referral_share = msg.referral_share.unwrap_or_default();
+    if referral_share > Decimal::one() {
+        return Err(ContractError::InvalidReferralShare {});
+    }
+
+    if let Some(end_time) = msg.end_time {
+        let start_time = msg.start_time.unwrap_or(env.block.time);
+        if end_time <= start_time {
+            return Err(ContractError::InvalidTimeWindow {});
+        }
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            denom: denom.clone(),
+            commission,
+            start_time: msg.start_time,
+            end_time: msg.end_time,
+            description: msg.description,
+            allowlist_enabled: msg.allowlist.is_some(),
+            eligibility_registry,
+            bond: msg.bond,
+            provisional_window: msg.provisional_window,
+            escrow,
+            pause_retract: msg.pause_retract,
+            fee_split: validate_fee_split(deps.api, msg.fee_split.unwrap_or_default())?,
+            referral_share,
+            commission_schedule: msg
+                .commission_schedule
+                .map(validate_commission_schedule)
+                .transpose()?,
+            refund_commissions: msg.refund_commissions,
+            opened_at: msg.start_time.unwrap_or(env.block.time),
+        },
+    )?;
+    PAUSED.save(deps.storage, &false)?;
+    CANCELLED.save(deps.storage, &false)?;
+    CLOSED.save(deps.storage, &false)?;
+    BIDDER_COUNT.save(deps.storage, &0)?;
+    NEXT_PAYOUT_ID.save(deps.storage, &PAYOUT_REPLY_ID)?;
+    COLLECTED_FEES.save(deps.storage, &CollectedFees::default())?;
+
+    if let Some(guardian) = guardian {
+        ROLES.save(
+            deps.storage,
+            (Role::Guardian.as_str(), &guardian),
+            &Empty {},
+        )?;
+    }
+
+    for address in msg.allowlist.unwrap_or_default() {
+        let address = validate_address(deps.api, &address)?;
+        ALLOWLIST.save(deps.storage, &address, &Empty {})?;
+    }
+
+    HIGHEST_BID.save(
         deps.storage,
-        HIGHEST_BID_KEY.to_string(),
         &State {
             address: info.sender.clone(),
-            bid: Coin::new(0, ATOM),
-            commission: Coin::new(0, ATOM),
+            bid: Coin::new(0, &denom),
+            commission: Coin::new(0, &denom),
         },
     )?;
 
-    if msg.owner.is_some() {
-        OWNER.save(deps.storage, &msg.owner.unwrap())?;
-    } else {
-        OWNER.save(deps.storage, &info.sender)?;
-    }
+    OWNER.save(deps.storage, &owner)?;
 
     Ok(Response::new())
 }
 
+/// Hooks are only replied to when they fail, payouts always so their pending entry is removed
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        HOOK_REPLY_ID => {
+            let error = msg.result.into_result().err().unwrap_or_default();
+            let resp = Response::new()
+                .add_attribute("action", "hook_failed")
+                .add_attribute("error", error);
+
+            Ok(resp)
+        }
+        id if id >= PAYOUT_REPLY_ID => {
+            let payout = PAYOUTS
+                .may_load(deps.storage, id)?
+                .ok_or(ContractError::UnknownReplyId { id })?;
+            PAYOUTS.remove(deps.storage, id);
+
+            let error = match msg.result.into_result() {
+                Ok(_) => return Ok(Response::new()),
+                Err(error) => error,
+            };
+
+            CREDITS.update(deps.storage, &payout.recipient, |credit| -> StdResult<_> {
+                Ok(credit.unwrap_or_default() + payout.amount.amount)
+            })?;
+
+            let resp = Response::new()
+                .add_attribute("action", "payout_failed")
+                .add_attribute("recipient", payout.recipient.as_str())
+                .add_attribute("amount", payout.amount.to_string())
+                .add_attribute("error", error);
+
+            Ok(resp)
+        }
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+fn validate_address(api: &dyn Api, address: &str) -> Result<Addr, ContractError> {
+    api.addr_validate(address)
+        .map_err(|_| ContractError::InvalidAddress {
+            address: address.to_string(),
+        })
+}
+
+fn validate_fee_split(
+    api: &dyn Api,
+    fee_split: Vec<(String, Decimal)>,
+) -> Result<Vec<(Addr, Decimal)>, ContractError> {
+    if fee_split.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut total = Decimal::zero();
+    let fee_split = fee_split
+        .into_iter()
+        .map(|(address, weight)| {
+            if weight.is_zero() {
+                return Err(ContractError::InvalidFeeSplit {});
+            }
+            total += weight;
+            Ok((validate_address(api, &address)?, weight))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if total != Decimal::one() {
+        return Err(ContractError::InvalidFeeSplit {});
+    }
+
+    Ok(fee_split)
+}
+
+fn validate_commission_schedule(
+    schedule: CommissionSchedule,
+) -> Result<CommissionSchedule, ContractError> {
+    let tiers: Vec<(u128, Decimal)> = match &schedule {
+        CommissionSchedule::Volume { tiers } => tiers
+            .iter()
+            .map(|tier| (tier.from.u128(), tier.rate))
+            .collect(),
+        CommissionSchedule::Time { tiers } => tiers
+            .iter()
+            .map(|tier| (tier.after.into(), tier.rate))
+            .collect(),
+    };
+
+    let valid = tiers.first().is_some_and(|(from, _)| *from == 0)
+        && tiers.iter().all(|(_, rate)| *rate <= Decimal::one())
+        && tiers
+            .windows(2)
+            .all(|pair| pair[0].0 < pair[1].0 && pair[0].1 >= pair[1].1);
+    if !valid {
+        return Err(ContractError::InvalidCommissionSchedule {});
+    }
+
+    Ok(schedule)
+}
+
+/// Commission on `amount` bid by an address whose total bid was `previous`. Volume tiers are
+/// applied like tax brackets, each part of the bid is charged at the rate of its tier.
+pub fn calculate_commission(
+    config: &Config,
+    previous: Uint128,
+    amount: Uint128,
+    block: &BlockInfo,
+) -> Uint128 {
+    match &config.commission_schedule {
+        None => amount * config.commission,
+        Some(CommissionSchedule::Volume { tiers }) => {
+            let (low, high) = (previous, previous + amount);
+
+            tiers
+                .iter()
+                .enumerate()
+                .map(|(idx, tier)| {
+                    let upper = tiers.get(idx + 1).map_or(high, |next| next.from.min(high));
+                    let lower = tier.from.max(low);
+                    upper.saturating_sub(lower) * tier.rate
+                })
+                .sum()
+        }
+        Some(CommissionSchedule::Time { tiers }) => {
+            let elapsed = block
+                .time
+                .seconds()
+                .saturating_sub(config.opened_at.seconds());
+            let rate = tiers
+                .iter()
+                .rev()
+                .find(|tier| tier.after <= elapsed)
+                .map_or(config.commission, |tier| tier.rate);
+
+            amount * rate
+        }
+    }
+}
+
+/// Derives the auction phase from the escrow, the settlement, the cancellation, the stored winner
+/// and the configured time window.
+pub fn status(storage: &dyn Storage, block: &BlockInfo) -> StdResult<AuctionStatus> {
+    let config = CONFIG.load(storage)?;
+
+    if let Some(escrow) = ESCROW.may_load(storage)? {
+        return Ok(if escrow.released {
+            AuctionStatus::Settled
+        } else {
+            AuctionStatus::InEscrow
+        });
+    }
+
+    if let Some(settlement) = SETTLEMENT.may_load(storage)? {
+        if settlement.confirmed {
+            return Ok(AuctionStatus::Settled);
+        }
+
+        if config.provisional_window.is_some() {
+            return Ok(if settlement.slashed {
+                AuctionStatus::Unsold
+            } else {
+                AuctionStatus::AwaitingConfirmation
+            });
+        }
+    }
+
+    if CANCELLED.load(storage)? {
+        return Ok(AuctionStatus::Cancelled);
+    }
+
+    if CLOSED.load(storage)? {
+        return Ok(AuctionStatus::Closed);
+    }
+
+    if config.end_time.is_some_and(|end| block.time >= end) {
+        return Ok(AuctionStatus::Closed);
+    }
+    if config.start_time.is_some_and(|start| block.time < start) {
+        return Ok(AuctionStatus::NotStarted);
+    }
+
+    Ok(AuctionStatus::Open)
+}
+
 pub mod query {
-    use cosmwasm_std::{Addr, Coin, Deps, StdResult};
+    use cosmwasm_std::{Addr, Coin, Deps, Empty, Env, Order, StdResult};
+    use cw_storage_plus::{Bound, Map};
+
+    use crate::msg::{
+        AddressBidResp, AddressListResp, AllBidsResp, BidHistoryEntry, BidHistoryResp, BidInfo,
+        BondResp, ConfigResp, CreditResp, DepositResp, EscrowConfig, EscrowInfo, EscrowResp,
+        FeesResp, HighestBidResp, HooksResp, OwnershipResp, ReferralsResp, Role, RolesResp,
+        SettlementInfo, SettlementResp, SimulateBidResp, StatusResp, TopBidsResp, WinnerInfo,
+        WinnerResp,
+    };
+    use crate::state::{
+        bids, State, ALLOWLIST, BIDDER_COUNT, BID_HISTORY, BONDS, COLLECTED_FEES, CONFIG, CREDITS,
+        DENYLIST, DEPOSITS, ESCROW, FORFEITED, HIGHEST_BID, HOOKS, OWNER, PAUSED, PENDING_OWNER,
+        REFERRALS, ROLES, SETTLEMENT, WINNER,
+    };
 
-    use crate::msg::{AddressBidResp, HighestBidResp, WinnerResp};
-    use crate::state::STATE;
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
 
-    use super::{ATOM, HIGHEST_BID_KEY};
+    fn bid_info(state: State) -> BidInfo {
+        let net_bid = Coin::new(
+            state.bid.amount.u128() - state.commission.amount.u128(),
+            state.bid.denom.clone(),
+        );
+
+        BidInfo {
+            address: state.address,
+            bid: state.bid,
+            net_bid,
+            commission: state.commission,
+        }
+    }
 
     pub fn highest_bid(deps: Deps) -> StdResult<HighestBidResp> {
-        let highest_bid_info = STATE.load(deps.storage, HIGHEST_BID_KEY.to_string())?;
+        let highest_bid_info = HIGHEST_BID.load(deps.storage)?;
 
         Ok(HighestBidResp {
             address: highest_bid_info.address,
@@ -55,214 +371,1831 @@ pub mod query {
     }
 
     pub fn address_bid(deps: Deps, address: String) -> StdResult<AddressBidResp> {
-        let address_bid_info = STATE.may_load(deps.storage, address.to_string())?;
+        let address = deps.api.addr_validate(&address)?;
+        let address_bid_info = bids().may_load(deps.storage, &address)?;
 
-        if address_bid_info.is_some() {
-            Ok(AddressBidResp {
-                bid: address_bid_info.unwrap().bid,
-            })
-        } else {
-            Ok(AddressBidResp {
-                bid: Coin::new(0, ATOM),
+        Ok(AddressBidResp {
+            bid: address_bid_info.map(|info| info.bid),
+        })
+    }
+
+    pub fn all_bids(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllBidsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|address| deps.api.addr_validate(&address))
+            .transpose()?;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let bids_page = bids()
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, state)| bid_info(state)))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let total_count = BIDDER_COUNT.load(deps.storage)?;
+
+        Ok(AllBidsResp {
+            bids: bids_page,
+            total_count,
+        })
+    }
+
+    pub fn top_bids(deps: Deps, limit: Option<u32>) -> StdResult<TopBidsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let top = bids()
+            .idx
+            .amount
+            .range(deps.storage, None, None, Order::Descending)
+            .take(limit)
+            .map(|item| item.map(|(_, state)| bid_info(state)))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(TopBidsResp { bids: top })
+    }
+
+    pub fn bid_history(
+        deps: Deps,
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<BidHistoryResp> {
+        let address = deps.api.addr_validate(&address)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let history = BID_HISTORY
+            .prefix(&address)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (id, record) = item?;
+
+                Ok(BidHistoryEntry {
+                    id,
+                    timestamp: record.timestamp,
+                    height: record.height,
+                    amount: record.amount,
+                    commission: record.commission,
+                    total: record.total,
+                })
             })
-        }
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(BidHistoryResp { bids: history })
     }
 
     pub fn winner(deps: Deps) -> StdResult<WinnerResp> {
-        let winner_info = STATE.may_load(deps.storage, "winner".to_string())?;
+        let winner_info = WINNER
+            .may_load(deps.storage)?
+            .filter(|info| !FORFEITED.has(deps.storage, &info.address));
 
-        if winner_info.is_some() {
-            let winner_info = winner_info.unwrap();
-            Ok(WinnerResp {
-                address: winner_info.address,
-                bid: winner_info.bid,
-            })
-        } else {
-            Ok(WinnerResp {
-                address: Addr::unchecked(""),
-                bid: Coin::new(0, ATOM),
-            })
-        }
+        Ok(WinnerResp {
+            winner: winner_info.map(|info| WinnerInfo {
+                address: info.address,
+                bid: info.bid,
+            }),
+        })
+    }
+
+    pub fn status(deps: Deps, env: Env) -> StdResult<StatusResp> {
+        Ok(StatusResp {
+            status: super::status(deps.storage, &env.block)?,
+            paused: PAUSED.load(deps.storage)?,
+        })
+    }
+
+    pub fn config(deps: Deps) -> StdResult<ConfigResp> {
+        let config = CONFIG.load(deps.storage)?;
+        let owner = OWNER.may_load(deps.storage)?;
+
+        Ok(ConfigResp {
+            owner,
+            denom: config.denom,
+            commission: config.commission,
+            start_time: config.start_time,
+            end_time: config.end_time,
+            description: config.description,
+            allowlist_enabled: config.allowlist_enabled,
+            eligibility_registry: config.eligibility_registry,
+            bond: config.bond,
+            provisional_window: config.provisional_window,
+            escrow: config.escrow.map(|escrow| EscrowConfig {
+                arbiter: escrow.arbiter.into_string(),
+                timeout: escrow.timeout,
+            }),
+            pause_retract: config.pause_retract,
+            fee_split: config.fee_split,
+            referral_share: config.referral_share,
+            commission_schedule: config.commission_schedule,
+            refund_commissions: config.refund_commissions,
+        })
+    }
+
+    pub fn ownership(deps: Deps) -> StdResult<OwnershipResp> {
+        let owner = OWNER.may_load(deps.storage)?;
+        let pending = PENDING_OWNER.may_load(deps.storage)?;
+
+        Ok(OwnershipResp {
+            owner,
+            pending_owner: pending.as_ref().map(|pending| pending.owner.clone()),
+            pending_expiry: pending.and_then(|pending| pending.expiry),
+        })
+    }
+
+    pub fn hooks(deps: Deps) -> StdResult<HooksResp> {
+        let hooks = HOOKS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(HooksResp { hooks })
+    }
+
+    pub fn credit(deps: Deps, address: String) -> StdResult<CreditResp> {
+        let address = deps.api.addr_validate(&address)?;
+        let credit = CREDITS.may_load(deps.storage, &address)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(CreditResp {
+            credit: credit.map(|amount| Coin::new(amount.u128(), config.denom)),
+        })
+    }
+
+    pub fn deposit(deps: Deps, address: String) -> StdResult<DepositResp> {
+        let address = deps.api.addr_validate(&address)?;
+        let deposit = DEPOSITS.may_load(deps.storage, &address)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(DepositResp {
+            balance: Coin::new(
+                deposit.as_ref().map_or(0, |deposit| deposit.balance.u128()),
+                config.denom,
+            ),
+            nonce: deposit.as_ref().map_or(0, |deposit| deposit.nonce),
+            pubkey: deposit.map(|deposit| deposit.pubkey),
+        })
+    }
+
+    pub fn fees(deps: Deps) -> StdResult<FeesResp> {
+        let fees = COLLECTED_FEES.load(deps.storage)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(FeesResp {
+            accrued: Coin::new(fees.accrued.u128(), &config.denom),
+            withdrawn: Coin::new(fees.withdrawn.u128(), &config.denom),
+            available: Coin::new((fees.accrued - fees.withdrawn).u128(), &config.denom),
+        })
+    }
+
+    pub fn referrals(deps: Deps, referrer: String) -> StdResult<ReferralsResp> {
+        let referrer = deps.api.addr_validate(&referrer)?;
+        let referral = REFERRALS
+            .may_load(deps.storage, &referrer)?
+            .unwrap_or_default();
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(ReferralsResp {
+            bidders: referral.bidders,
+            rewards: Coin::new(referral.rewards.u128(), config.denom),
+        })
+    }
+
+    pub fn simulate_bid(
+        deps: Deps,
+        env: Env,
+        address: String,
+        funds: Vec<Coin>,
+    ) -> StdResult<SimulateBidResp> {
+        let address = deps.api.addr_validate(&address)?;
+
+        let resp = match super::exec::validate_bid(deps, &env, &address, &funds) {
+            Ok(bid) => SimulateBidResp::Accepted {
+                commission: bid.commission,
+                total: bid.total,
+            },
+            Err(err) => SimulateBidResp::Rejected {
+                error: err.to_string(),
+            },
+        };
+
+        Ok(resp)
+    }
+
+    pub fn roles(deps: Deps) -> StdResult<RolesResp> {
+        let holders = |role: Role| {
+            ROLES
+                .prefix(role.as_str())
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()
+        };
+
+        Ok(RolesResp {
+            owner: OWNER.may_load(deps.storage)?,
+            operators: holders(Role::Operator)?,
+            fee_admins: holders(Role::FeeAdmin)?,
+            guardians: holders(Role::Guardian)?,
+        })
+    }
+
+    fn list_addresses(
+        deps: Deps,
+        list: Map<&Addr, Empty>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AddressListResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|address| deps.api.addr_validate(&address))
+            .transpose()?;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let addresses = list
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(AddressListResp { addresses })
+    }
+
+    pub fn allowlist(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AddressListResp> {
+        list_addresses(deps, ALLOWLIST, start_after, limit)
+    }
+
+    pub fn denylist(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AddressListResp> {
+        list_addresses(deps, DENYLIST, start_after, limit)
+    }
+
+    pub fn bond(deps: Deps, address: String) -> StdResult<BondResp> {
+        let address = deps.api.addr_validate(&address)?;
+        let bond = BONDS.may_load(deps.storage, &address)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(BondResp {
+            bond: bond.map(|amount| Coin::new(amount.u128(), config.denom)),
+        })
+    }
+
+    pub fn settlement(deps: Deps) -> StdResult<SettlementResp> {
+        let settlement = SETTLEMENT.may_load(deps.storage)?;
+
+        Ok(SettlementResp {
+            settlement: settlement.map(|settlement| SettlementInfo {
+                deadline: settlement.deadline,
+                confirmed: settlement.confirmed,
+                slashed: settlement.slashed,
+            }),
+        })
+    }
+
+    pub fn escrow(deps: Deps) -> StdResult<EscrowResp> {
+        let escrow = ESCROW.may_load(deps.storage)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(EscrowResp {
+            escrow: escrow.map(|escrow| EscrowInfo {
+                buyer: escrow.buyer,
+                amount: Coin::new(escrow.amount.u128(), config.denom),
+                deadline: escrow.deadline,
+                disputed: escrow.disputed,
+                released: escrow.released,
+            }),
+        })
     }
 }
 
 pub mod exec {
-    use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response, Uint128};
+    use cosmwasm_std::{
+        to_binary, to_vec, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env,
+        MessageInfo, Order, Response, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+    };
+    use cw_storage_plus::Map;
+    use cw_utils::Expiration;
+    use sha2::{Digest, Sha256};
 
     use crate::{
         error::ContractError,
-        state::{State, OWNER, STATE},
+        events::{BidEvent, CloseEvent, RetractEvent},
+        msg::{
+            AuctionStatus, BidIntent, BiddingHookExecuteMsg, BiddingHookMsg, IsEligibleResp,
+            RegistryQueryMsg, Role,
+        },
+        state::{
+            bids, BidRecord, Config, Deposit, Escrow, Payout, PendingOwner, Settlement, State,
+            ALLOWLIST, BIDDER_COUNT, BID_HISTORY, BONDS, CANCELLED, CLOSED, COLLECTED_FEES, CONFIG,
+            CREDITS, DENYLIST, DEPOSITS, ESCROW, FORFEITED, HIGHEST_BID, HOOKS, NEXT_PAYOUT_ID,
+            OWNER, PAUSED, PAYOUTS, PENDING_OWNER, REFERRALS, REFERRERS, ROLES, SETTLEMENT, WINNER,
+        },
     };
 
-    use super::{ATOM, COMMISSION, HIGHEST_BID_KEY, WINNER_KEY};
+    use super::{validate_address, validate_fee_split, HOOK_GAS_LIMIT, HOOK_REPLY_ID};
 
-    pub fn bid(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-        let owner = OWNER.load(deps.storage)?;
-        if info.sender == owner {
-            return Err(ContractError::OwnerCannotBid {});
-        }
+    fn load_owner(storage: &dyn Storage) -> Result<Addr, ContractError> {
+        OWNER.may_load(storage)?.ok_or(ContractError::NoOwner {})
+    }
 
-        let winner = STATE.may_load(deps.storage, WINNER_KEY.to_string())?;
-        if winner.is_some() {
-            return Err(ContractError::BiddingAlreadyClosed {});
+    fn pending_settlement(storage: &dyn Storage) -> StdResult<Option<Settlement>> {
+        let settlement = SETTLEMENT.may_load(storage)?;
+        Ok(settlement.filter(|settlement| !settlement.confirmed && !settlement.slashed))
+    }
+
+    fn pending_escrow(storage: &dyn Storage) -> Result<Escrow, ContractError> {
+        ESCROW
+            .may_load(storage)?
+            .filter(|escrow| !escrow.released)
+            .ok_or(ContractError::NoEscrow {})
+    }
+
+    /// Pays the winning bid net of commission to the owner, or into escrow when it is configured
+    fn pay_proceeds(
+        storage: &mut dyn Storage,
+        env: &Env,
+        config: &Config,
+        winner: &State,
+    ) -> Result<Option<SubMsg>, ContractError> {
+        let amount = winner.bid.amount - winner.commission.amount;
+
+        if let Some(escrow) = &config.escrow {
+            ESCROW.save(
+                storage,
+                &Escrow {
+                    buyer: winner.address.clone(),
+                    amount,
+                    deadline: env.block.time.plus_seconds(escrow.timeout),
+                    disputed: false,
+                    released: false,
+                },
+            )?;
+
+            return Ok(None);
         }
 
-        let highest_bid_info = STATE.load(deps.storage, HIGHEST_BID_KEY.to_string())?;
-        let mut resp = Response::default();
+        let owner = load_owner(storage)?;
+        let proceeds = Coin::new(amount.u128(), &config.denom);
+        Ok(Some(payout(storage, &owner, proceeds)?))
+    }
 
-        let native_coin_bid = info.funds.iter().find(|coin| coin.denom == ATOM);
+    /// Sends `amount` to `recipient` under a fresh reply id. A failed transfer doesn't revert the
+    /// transaction, `reply` credits it to the recipient instead.
+    fn payout(storage: &mut dyn Storage, recipient: &Addr, amount: Coin) -> StdResult<SubMsg> {
+        let id = NEXT_PAYOUT_ID.load(storage)?;
+        NEXT_PAYOUT_ID.save(storage, &(id + 1))?;
 
-        if native_coin_bid.is_some() {
-            let native_coin_bid = native_coin_bid.unwrap();
-            let address_bid_info = STATE.may_load(deps.storage, info.sender.to_string())?;
-            let mut total_address_bid = native_coin_bid.amount;
-            if address_bid_info.is_some() {
-                total_address_bid += address_bid_info.clone().unwrap().bid.amount
-            }
+        PAYOUTS.save(
+            storage,
+            id,
+            &Payout {
+                recipient: recipient.clone(),
+                amount: amount.clone(),
+            },
+        )?;
 
-            if total_address_bid > highest_bid_info.bid.amount {
-                let commission = Coin::new(
-                    (native_coin_bid.amount.u128() * COMMISSION / 100) as u128,
-                    native_coin_bid.clone().denom,
-                );
+        let bank_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: [amount].to_vec(),
+        };
+        Ok(SubMsg::reply_always(bank_msg, id))
+    }
 
-                let mut total_commission = commission.amount;
-                if address_bid_info.is_some() {
-                    total_commission += address_bid_info.unwrap().commission.amount
-                }
+    /// Notifies every registered hook with at most `HOOK_GAS_LIMIT` gas each. A failing hook is
+    /// only reported in `reply`, so it cannot block the auction
+    fn hook_messages(storage: &dyn Storage, msg: BiddingHookMsg) -> StdResult<Vec<SubMsg>> {
+        let msg = to_binary(&BiddingHookExecuteMsg::BiddingHook(msg))?;
 
-                STATE.save(
-                    deps.storage,
-                    info.sender.to_string(),
-                    &State {
-                        address: info.sender.clone(),
-                        bid: Coin::new(total_address_bid.u128(), ATOM),
-                        commission: Coin::new(total_commission.u128(), ATOM),
-                    },
-                )?;
+        HOOKS
+            .keys(storage, None, None, Order::Ascending)
+            .map(|hook| {
+                let execute = WasmMsg::Execute {
+                    contract_addr: hook?.into_string(),
+                    msg: msg.clone(),
+                    funds: vec![],
+                };
+                Ok(SubMsg::reply_on_error(execute, HOOK_REPLY_ID).with_gas_limit(HOOK_GAS_LIMIT))
+            })
+            .collect()
+    }
 
-                STATE.save(
-                    deps.storage,
-                    HIGHEST_BID_KEY.to_string(),
-                    &State {
-                        address: info.sender.clone(),
-                        bid: Coin::new(total_address_bid.u128(), ATOM),
-                        commission: Coin::new(total_commission.u128(), ATOM),
-                    },
-                )?;
+    /// Adds a commission to the fees, less the share credited to the bidder's referrer
+    fn collect_commission(
+        storage: &mut dyn Storage,
+        config: &Config,
+        bidder: &Addr,
+        commission: Uint128,
+    ) -> StdResult<()> {
+        let mut reward = Uint128::zero();
+        if let Some(referrer) = REFERRERS.may_load(storage, bidder)? {
+            reward = commission * config.referral_share;
+            REFERRALS.update(storage, &referrer, |referral| -> StdResult<_> {
+                let mut referral = referral.unwrap_or_default();
+                referral.rewards += reward;
+                Ok(referral)
+            })?;
+            CREDITS.update(storage, &referrer, |credit| -> StdResult<_> {
+                Ok(credit.unwrap_or_default() + reward)
+            })?;
+        }
 
-                resp = resp
-                    .add_attribute("action", "bid")
-                    .add_attribute("sender", info.sender.as_str())
-                    .add_attribute(HIGHEST_BID_KEY, highest_bid_info.bid.to_string());
+        COLLECTED_FEES.update(storage, |mut fees| -> StdResult<_> {
+            fees.accrued += commission - reward;
+            Ok(fees)
+        })?;
 
-                if commission.amount > Uint128::new(0) {
-                    let bank_msg = BankMsg::Send {
-                        to_address: owner.to_string(),
-                        amount: [commission.clone()].to_vec(),
-                    };
+        Ok(())
+    }
 
-                    Ok(resp.add_message(bank_msg))
-                } else {
-                    Ok(resp)
-                }
-            } else {
-                return Err(ContractError::InsufficientBid {
-                    bid: total_address_bid.to_string(),
-                    highest_bid: highest_bid_info.bid.amount.to_string(),
-                }
-                .into());
-            }
-        } else {
-            return Err(ContractError::IncorrectBid {}.into());
+    fn ensure_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+        if PAUSED.load(storage)? {
+            return Err(ContractError::Paused {});
         }
+
+        Ok(())
     }
 
-    pub fn close(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-        let owner = OWNER.load(deps.storage)?;
+    fn ensure_owner(storage: &dyn Storage, sender: &Addr) -> Result<Addr, ContractError> {
+        let owner = load_owner(storage)?;
 
-        if info.sender != owner {
+        if *sender != owner {
             return Err(ContractError::Unauthorized {
                 owner: owner.into(),
             });
         }
 
-        let winner = STATE.may_load(deps.storage, WINNER_KEY.to_string())?;
-        if winner.is_some() {
-            return Err(ContractError::BiddingAlreadyClosed {});
+        Ok(owner)
+    }
+
+    /// The owner is paid the proceeds, so it must not be able to win its own auction
+    pub(super) fn ensure_not_bidder(
+        storage: &dyn Storage,
+        address: &Addr,
+    ) -> Result<(), ContractError> {
+        if bids().has(storage, address) {
+            return Err(ContractError::BidderCannotOwn {});
         }
 
-        let highest_bid_info = STATE.load(deps.storage, HIGHEST_BID_KEY.to_string())?;
+        Ok(())
+    }
 
-        STATE.save(
-            deps.storage,
-            WINNER_KEY.to_string(),
-            &State {
-                address: highest_bid_info.address,
-                bid: highest_bid_info.bid.clone(),
-                commission: highest_bid_info.commission.clone(),
-            },
-        )?;
+    /// Passes for the owner and for any address holding one of the given roles
+    fn ensure_role(
+        storage: &dyn Storage,
+        sender: &Addr,
+        roles: &[Role],
+    ) -> Result<(), ContractError> {
+        if roles
+            .iter()
+            .any(|role| ROLES.has(storage, (role.as_str(), sender)))
+        {
+            return Ok(());
+        }
 
-        let resp = Response::new()
-            .add_attribute("action", "close")
-            .add_attribute("sender", info.sender.as_str());
+        ensure_owner(storage, sender)?;
+        Ok(())
+    }
 
-        if highest_bid_info.bid.amount > Uint128::new(0) {
-            let to_be_paid = Coin::new(
-                highest_bid_info.bid.amount.u128() - highest_bid_info.commission.amount.u128(),
-                highest_bid_info.bid.denom,
-            );
-            let bank_msg = BankMsg::Send {
-                to_address: owner.to_string(),
-                amount: [to_be_paid.clone()].to_vec(),
-            };
-            Ok(resp.add_message(bank_msg))
-        } else {
-            Ok(resp)
-        }
+    fn bidding_closed(storage: &dyn Storage) -> StdResult<bool> {
+        Ok(CLOSED.load(storage)? || CANCELLED.load(storage)?)
     }
 
-    pub fn retract(
-        deps: DepsMut,
-        _env: Env,
-        info: MessageInfo,
-        receiver: Option<String>,
-    ) -> Result<Response, ContractError> {
-        let winner = STATE.may_load(deps.storage, WINNER_KEY.to_string())?;
-        if winner.is_none() {
-            return Err(ContractError::BiddingNotClosed {});
+    /// Outcome of a bid that passed every check of `validate_bid`
+    pub(super) struct ValidBid {
+        /// Funds of the bid in the auction denom
+        pub amount: Coin,
+        pub commission: Coin,
+        /// Bidder's total bid after this one
+        pub total: Coin,
+        /// Bidder's state before this bid
+        pub previous: Option<State>,
+        pub highest: State,
+    }
+
+    /// Runs every check of `Bid {}` without touching the state, shared with `SimulateBid {}`
+    pub(super) fn validate_bid(
+        deps: Deps,
+        env: &Env,
+        sender: &Addr,
+        funds: &[Coin],
+    ) -> Result<ValidBid, ContractError> {
+        ensure_not_paused(deps.storage)?;
+
+        let owner = load_owner(deps.storage)?;
+        if *sender == owner {
+            return Err(ContractError::OwnerCannotBid {});
         }
 
-        if winner.unwrap().address == info.sender {
-            return Err(ContractError::WinnerCannotRetract {});
+        if bidding_closed(deps.storage)? {
+            return Err(ContractError::BiddingAlreadyClosed {});
         }
 
-        let address_bid_info = STATE.may_load(deps.storage, info.sender.to_string())?;
+        let config = CONFIG.load(deps.storage)?;
+        let permitted = !DENYLIST.has(deps.storage, sender)
+            && (!config.allowlist_enabled || ALLOWLIST.has(deps.storage, sender));
+        if !permitted {
+            return Err(ContractError::NotPermitted {
+                address: sender.into(),
+            });
+        }
 
-        if address_bid_info.is_some() {
-            let address_bid_info = address_bid_info.unwrap();
-            let receiver = receiver.unwrap_or(info.sender.to_string());
-            let to_be_returned = Coin::new(
-                address_bid_info.bid.amount.u128() - address_bid_info.commission.amount.u128(),
-                address_bid_info.bid.denom,
-            );
+        if let Some(registry) = &config.eligibility_registry {
+            let resp: IsEligibleResp = deps.querier.query_wasm_smart(
+                registry,
+                &RegistryQueryMsg::IsEligible {
+                    address: sender.to_string(),
+                },
+            )?;
 
-            let bank_msg = BankMsg::Send {
-                to_address: receiver,
-                amount: [to_be_returned].to_vec(),
-            };
+            if !resp.eligible {
+                return Err(ContractError::NotEligible {
+                    address: sender.into(),
+                });
+            }
+        }
 
-            let resp = Response::new()
-                .add_message(bank_msg)
-                .add_attribute("action", "retract")
-                .add_attribute("sender", info.sender.as_str());
+        if config.bond.is_some() && !BONDS.has(deps.storage, sender) {
+            return Err(ContractError::NotRegistered {});
+        }
 
-            Ok(resp)
-        } else {
+        if let Some(start_time) = config.start_time {
+            if env.block.time < start_time {
+                return Err(ContractError::BiddingNotStarted {});
+            }
+        }
+        if let Some(end_time) = config.end_time {
+            if env.block.time >= end_time {
+                return Err(ContractError::BiddingAlreadyClosed {});
+            }
+        }
+
+        let amount = funds
+            .iter()
+            .find(|coin| coin.denom == config.denom)
+            .ok_or(ContractError::IncorrectBid {})?;
+
+        let highest = HIGHEST_BID.load(deps.storage)?;
+        let previous = bids().may_load(deps.storage, sender)?;
+        let previous_total = previous
+            .as_ref()
+            .map_or(Uint128::zero(), |state| state.bid.amount);
+        let total = previous_total + amount.amount;
+
+        if total <= highest.bid.amount {
+            return Err(ContractError::InsufficientBid {
+                bid: total.to_string(),
+                highest_bid: highest.bid.amount.to_string(),
+            });
+        }
+
+        let commission =
+            super::calculate_commission(&config, previous_total, amount.amount, &env.block);
+
+        Ok(ValidBid {
+            amount: amount.clone(),
+            commission: Coin::new(commission.u128(), &config.denom),
+            total: Coin::new(total.u128(), &config.denom),
+            previous,
+            highest,
+        })
+    }
+
+    pub fn bid(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        referrer: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let ValidBid {
+            amount,
+            commission,
+            total,
+            previous,
+            highest: highest_bid_info,
+        } = validate_bid(deps.as_ref(), &env, &info.sender, &info.funds)?;
+
+        let referrer = referrer
+            .map(|referrer| validate_address(deps.api, &referrer))
+            .transpose()?;
+        if referrer.as_ref() == Some(&info.sender) {
+            return Err(ContractError::SelfReferral {});
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let mut resp = Response::default();
+
+        let mut total_commission = commission.amount;
+        match previous {
+            Some(previous) => total_commission += previous.commission.amount,
+            None => {
+                BIDDER_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+            }
+        }
+
+        let state = State {
+            address: info.sender.clone(),
+            bid: total.clone(),
+            commission: Coin::new(total_commission.u128(), &config.denom),
+        };
+        bids().save(deps.storage, &info.sender, &state)?;
+        HIGHEST_BID.save(deps.storage, &state)?;
+
+        let next_id = BID_HISTORY
+            .prefix(&info.sender)
+            .keys(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+            .map_or(0, |id| id + 1);
+
+        BID_HISTORY.save(
+            deps.storage,
+            (&info.sender, next_id),
+            &BidRecord {
+                timestamp: env.block.time,
+                height: env.block.height,
+                amount: amount.clone(),
+                commission: commission.clone(),
+                total: total.clone(),
+            },
+        )?;
+
+        let previous_leader =
+            (!highest_bid_info.bid.amount.is_zero()).then(|| highest_bid_info.address.clone());
+
+        if previous_leader
+            .as_ref()
+            .is_some_and(|leader| *leader != info.sender)
+        {
+            resp = resp.add_submessages(hook_messages(
+                deps.storage,
+                BiddingHookMsg::Outbid {
+                    bidder: highest_bid_info.address.clone(),
+                    total: highest_bid_info.bid.clone(),
+                    outbid_by: info.sender.clone(),
+                },
+            )?);
+        }
+        resp = resp.add_submessages(hook_messages(
+            deps.storage,
+            BiddingHookMsg::NewHighestBid {
+                bidder: info.sender.clone(),
+                total: total.clone(),
+            },
+        )?);
+
+        // `highest_bid` holds the previous highest bid, it is kept for existing clients
+        resp = resp
+            .add_attribute("action", "bid")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("highest_bid", highest_bid_info.bid.to_string())
+            .add_event(
+                BidEvent {
+                    bidder: info.sender.clone(),
+                    amount,
+                    commission: commission.clone(),
+                    total,
+                    previous_leader,
+                    leader: info.sender.clone(),
+                }
+                .into(),
+            );
+
+        if let (0, Some(referrer)) = (next_id, &referrer) {
+            REFERRERS.save(deps.storage, &info.sender, referrer)?;
+            REFERRALS.update(deps.storage, referrer, |referral| -> StdResult<_> {
+                let mut referral = referral.unwrap_or_default();
+                referral.bidders += 1;
+                Ok(referral)
+            })?;
+        }
+
+        if !config.refund_commissions {
+            collect_commission(deps.storage, &config, &info.sender, commission.amount)?;
+        }
+
+        Ok(resp)
+    }
+
+    pub fn close(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        ensure_role(deps.storage, &info.sender, &[Role::Operator])?;
+        ensure_not_paused(deps.storage)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "close")
+            .add_attribute("sender", info.sender.as_str());
+
+        close_bidding(deps, &env, resp)
+    }
+
+    /// Designates the highest bidder as the winner and pays or settles the proceeds, shared by
+    /// `close` and the governance `ForceClose`
+    pub(super) fn close_bidding(
+        deps: DepsMut,
+        env: &Env,
+        resp: Response,
+    ) -> Result<Response, ContractError> {
+        if bidding_closed(deps.storage)? {
+            return Err(ContractError::BiddingAlreadyClosed {});
+        }
+
+        let highest_bid_info = HIGHEST_BID.load(deps.storage)?;
+        let has_winning_bid = highest_bid_info.bid.amount > Uint128::new(0);
+
+        CLOSED.save(deps.storage, &true)?;
+        // Without bids `HIGHEST_BID` only holds the instantiation placeholder
+        if has_winning_bid {
+            WINNER.save(deps.storage, &highest_bid_info)?;
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+
+        if has_winning_bid && config.refund_commissions {
+            collect_commission(
+                deps.storage,
+                &config,
+                &highest_bid_info.address,
+                highest_bid_info.commission.amount,
+            )?;
+        }
+
+        let winner = has_winning_bid.then(|| highest_bid_info.address.clone());
+        let winning_bid = has_winning_bid.then(|| highest_bid_info.bid.clone());
+
+        let resp = resp
+            .add_event(
+                CloseEvent {
+                    winner: winner.clone(),
+                    winning_bid: winning_bid.clone(),
+                }
+                .into(),
+            )
+            .add_submessages(hook_messages(
+                deps.storage,
+                BiddingHookMsg::Closed {
+                    winner,
+                    winning_bid,
+                },
+            )?);
+
+        let settlement_period = match (config.provisional_window, &config.bond) {
+            (Some(window), _) => Some(window),
+            (None, Some(bond)) => Some(bond.settlement_period),
+            (None, None) => None,
+        };
+        if let (true, Some(settlement_period)) = (has_winning_bid, settlement_period) {
+            SETTLEMENT.save(
+                deps.storage,
+                &Settlement {
+                    deadline: env.block.time.plus_seconds(settlement_period),
+                    confirmed: false,
+                    slashed: false,
+                },
+            )?;
+        }
+
+        // A provisional winner only pays once the purchase is confirmed
+        if has_winning_bid && config.provisional_window.is_none() {
+            let winner = WINNER.load(deps.storage)?;
+            if let Some(payout) = pay_proceeds(deps.storage, env, &config, &winner)? {
+                return Ok(resp.add_submessage(payout));
+            }
+        }
+
+        Ok(resp)
+    }
+
+    pub fn retract(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        receiver: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        if config.pause_retract {
+            ensure_not_paused(deps.storage)?;
+        }
+
+        let closed = bidding_closed(deps.storage)?;
+        let ended = config.end_time.is_some_and(|end| env.block.time >= end);
+        if !closed && !ended {
+            return Err(ContractError::BiddingNotClosed {});
+        }
+
+        // Past `end_time` the highest bidder is the winner even before `close` designates them
+        let winner = match WINNER.may_load(deps.storage)? {
+            Some(winner) => Some(winner),
+            None if !closed => Some(HIGHEST_BID.load(deps.storage)?)
+                .filter(|highest| !highest.bid.amount.is_zero()),
+            None => None,
+        };
+        if winner.is_some_and(|winner| winner.address == info.sender)
+            && !FORFEITED.has(deps.storage, &info.sender)
+        {
+            return Err(ContractError::WinnerCannotRetract {});
+        }
+
+        let address_bid_info = bids().may_load(deps.storage, &info.sender)?;
+        let bond = BONDS.may_load(deps.storage, &info.sender)?;
+
+        if address_bid_info.is_none() && bond.is_none() {
             return Err(ContractError::NoBidFound {
                 address: info.sender.to_string(),
             });
         }
+
+        let receiver = match receiver {
+            Some(receiver) => validate_address(deps.api, &receiver)?,
+            None => info.sender.clone(),
+        };
+
+        let mut to_be_returned = Uint128::zero();
+
+        if let Some(address_bid_info) = address_bid_info {
+            bids().remove(deps.storage, &info.sender)?;
+            BIDDER_COUNT.update(deps.storage, |count| -> StdResult<_> { Ok(count - 1) })?;
+            to_be_returned += address_bid_info.bid.amount;
+
+            // Forfeited provisional winners had their commission collected when they were offered
+            // the lot
+            if !config.refund_commissions || FORFEITED.has(deps.storage, &info.sender) {
+                to_be_returned -= address_bid_info.commission.amount;
+            }
+        }
+
+        if let Some(bond) = bond {
+            BONDS.remove(deps.storage, &info.sender);
+            to_be_returned += bond;
+        }
+
+        let refund = Coin::new(to_be_returned.u128(), config.denom);
+
+        let resp = Response::new()
+            .add_attribute("action", "retract")
+            .add_attribute("sender", info.sender.as_str())
+            .add_event(
+                RetractEvent {
+                    bidder: info.sender.clone(),
+                    receiver: receiver.clone(),
+                    amount: refund.clone(),
+                }
+                .into(),
+            );
+
+        if to_be_returned > Uint128::zero() {
+            let payout = payout(deps.storage, &receiver, refund)?;
+            Ok(resp.add_submessage(payout))
+        } else {
+            Ok(resp)
+        }
+    }
+
+    pub fn propose_owner(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        new_owner: String,
+        expiry: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        let new_owner = validate_address(deps.api, &new_owner)?;
+        ensure_not_bidder(deps.storage, &new_owner)?;
+        if expiry.is_some_and(|expiry| expiry.is_expired(&env.block)) {
+            return Err(ContractError::OwnershipProposalExpired {});
+        }
+
+        PENDING_OWNER.save(
+            deps.storage,
+            &PendingOwner {
+                owner: new_owner.clone(),
+                expiry,
+            },
+        )?;
+
+        let resp = Response::new()
+            .add_attribute("action", "propose_owner")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("pending_owner", new_owner.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn accept_ownership(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let pending = PENDING_OWNER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingOwner {})?;
+
+        if info.sender != pending.owner {
+            return Err(ContractError::NotPendingOwner {
+                pending_owner: pending.owner.into(),
+            });
+        }
+
+        if pending
+            .expiry
+            .is_some_and(|expiry| expiry.is_expired(&env.block))
+        {
+            return Err(ContractError::OwnershipProposalExpired {});
+        }
+
+        // The pending owner may have bid after being proposed
+        ensure_not_bidder(deps.storage, &pending.owner)?;
+
+        OWNER.save(deps.storage, &pending.owner)?;
+        PENDING_OWNER.remove(deps.storage);
+
+        let resp = Response::new()
+            .add_attribute("action", "accept_ownership")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn renounce_ownership(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        // Proceeds and commissions are paid to the owner, so it has to stay around
+        // until the bidding is closed
+        if !bidding_closed(deps.storage)? {
+            return Err(ContractError::BiddingNotClosed {});
+        }
+        if pending_settlement(deps.storage)?.is_some() || pending_escrow(deps.storage).is_ok() {
+            return Err(ContractError::SettlementPending {});
+        }
+        let fees = COLLECTED_FEES.load(deps.storage)?;
+        if fees.accrued > fees.withdrawn {
+            return Err(ContractError::FeesPending {});
+        }
+
+        OWNER.remove(deps.storage);
+        PENDING_OWNER.remove(deps.storage);
+
+        let resp = Response::new()
+            .add_attribute("action", "renounce_ownership")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    fn update_list(
+        deps: DepsMut,
+        list: Map<&Addr, Empty>,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<(), ContractError> {
+        for address in add {
+            let address = validate_address(deps.api, &address)?;
+            list.save(deps.storage, &address, &Empty {})?;
+        }
+
+        for address in remove {
+            let address = validate_address(deps.api, &address)?;
+            list.remove(deps.storage, &address);
+        }
+
+        Ok(())
+    }
+
+    pub fn update_allowlist(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+        update_list(deps, ALLOWLIST, add, remove)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_allowlist")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn update_denylist(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+        update_list(deps, DENYLIST, add, remove)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_denylist")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn register(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let bond = config.bond.ok_or(ContractError::BondNotRequired {})?;
+
+        let owner = load_owner(deps.storage)?;
+        if info.sender == owner {
+            return Err(ContractError::OwnerCannotBid {});
+        }
+
+        match super::status(deps.storage, &env.block)? {
+            AuctionStatus::NotStarted | AuctionStatus::Open => {}
+            _ => return Err(ContractError::BiddingAlreadyClosed {}),
+        }
+
+        if BONDS.has(deps.storage, &info.sender) {
+            return Err(ContractError::AlreadyRegistered {});
+        }
+
+        let bond_coin = Coin::new(bond.amount.u128(), config.denom);
+        if info.funds != [bond_coin.clone()] {
+            return Err(ContractError::IncorrectBond {
+                bond: bond_coin.to_string(),
+            });
+        }
+
+        BONDS.save(deps.storage, &info.sender, &bond.amount)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "register")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn confirm_purchase(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let winner = WINNER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::BiddingNotClosed {})?;
+        if info.sender != winner.address {
+            return Err(ContractError::NotWinner {});
+        }
+
+        let mut settlement =
+            pending_settlement(deps.storage)?.ok_or(ContractError::NoSettlementPending {})?;
+
+        if env.block.time >= settlement.deadline {
+            return Err(ContractError::SettlementExpired {});
+        }
+
+        settlement.confirmed = true;
+        SETTLEMENT.save(deps.storage, &settlement)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let mut resp = Response::new()
+            .add_attribute("action", "confirm_purchase")
+            .add_attribute("sender", info.sender.as_str());
+
+        if config.provisional_window.is_some() {
+            if let Some(payout) = pay_proceeds(deps.storage, &env, &config, &winner)? {
+                resp = resp.add_submessage(payout);
+            }
+        }
+
+        if let Some(bond) = BONDS.may_load(deps.storage, &winner.address)? {
+            BONDS.remove(deps.storage, &winner.address);
+
+            let bank_msg = BankMsg::Send {
+                to_address: winner.address.to_string(),
+                amount: [Coin::new(bond.u128(), config.denom)].to_vec(),
+            };
+
+            Ok(resp.add_message(bank_msg))
+        } else {
+            Ok(resp)
+        }
+    }
+
+    /// Anyone can trigger it, the slashed bond always goes to the owner
+    pub fn slash_bond(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        if config.provisional_window.is_some() {
+            return Err(ContractError::ProvisionalSettlement {});
+        }
+
+        let winner = WINNER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::BiddingNotClosed {})?;
+
+        let mut settlement =
+            pending_settlement(deps.storage)?.ok_or(ContractError::NoSettlementPending {})?;
+
+        if env.block.time < settlement.deadline {
+            return Err(ContractError::SettlementNotExpired {});
+        }
+
+        settlement.slashed = true;
+        SETTLEMENT.save(deps.storage, &settlement)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "slash_bond")
+            .add_attribute("sender", info.sender.as_str());
+
+        if let Some(bond) = BONDS.may_load(deps.storage, &winner.address)? {
+            BONDS.remove(deps.storage, &winner.address);
+
+            let owner = load_owner(deps.storage)?;
+            let bank_msg = BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: [Coin::new(bond.u128(), config.denom)].to_vec(),
+            };
+
+            Ok(resp.add_message(bank_msg))
+        } else {
+            Ok(resp)
+        }
+    }
+
+    /// Forfeits the provisional winner who let the settlement window expire and offers the lot
+    /// to the next-highest bidder. Anyone can trigger it.
+    pub fn advance_settlement(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let window = config
+            .provisional_window
+            .ok_or(ContractError::NotProvisionalSettlement {})?;
+
+        let winner = WINNER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::BiddingNotClosed {})?;
+
+        let mut settlement =
+            pending_settlement(deps.storage)?.ok_or(ContractError::NoSettlementPending {})?;
+
+        if env.block.time < settlement.deadline {
+            return Err(ContractError::SettlementNotExpired {});
+        }
+
+        FORFEITED.save(deps.storage, &winner.address, &Empty {})?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "advance_settlement")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("forfeited", winner.address.as_str());
+
+        if let Some(bond) = BONDS.may_load(deps.storage, &winner.address)? {
+            BONDS.remove(deps.storage, &winner.address);
+
+            let owner = load_owner(deps.storage)?;
+            resp = resp.add_message(BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: [Coin::new(bond.u128(), &config.denom)].to_vec(),
+            });
+        }
+
+        let runner_up = bids()
+            .idx
+            .amount
+            .range(deps.storage, None, None, Order::Descending)
+            .map(|item| item.map(|(_, state)| state))
+            .find(|item| match item {
+                Ok(state) => !FORFEITED.has(deps.storage, &state.address),
+                Err(_) => true,
+            })
+            .transpose()?;
+
+        if let Some(runner_up) = runner_up {
+            if config.refund_commissions {
+                collect_commission(
+                    deps.storage,
+                    &config,
+                    &runner_up.address,
+                    runner_up.commission.amount,
+                )?;
+            }
+
+            settlement.deadline = env.block.time.plus_seconds(window);
+            resp = resp.add_attribute("provisional_winner", runner_up.address.as_str());
+            WINNER.save(deps.storage, &runner_up)?;
+        } else {
+            settlement.slashed = true;
+        }
+
+        SETTLEMENT.save(deps.storage, &settlement)?;
+
+        Ok(resp)
+    }
+
+    pub fn confirm_delivery(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let mut escrow = pending_escrow(deps.storage)?;
+        if info.sender != escrow.buyer {
+            return Err(ContractError::NotWinner {});
+        }
+        if escrow.disputed {
+            return Err(ContractError::EscrowDisputed {});
+        }
+
+        escrow.released = true;
+        ESCROW.save(deps.storage, &escrow)?;
+
+        let owner = load_owner(deps.storage)?;
+        let config = CONFIG.load(deps.storage)?;
+        let bank_msg = BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: [Coin::new(escrow.amount.u128(), config.denom)].to_vec(),
+        };
+
+        let resp = Response::new()
+            .add_message(bank_msg)
+            .add_attribute("action", "confirm_delivery")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn dispute(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let mut escrow = pending_escrow(deps.storage)?;
+        if info.sender != escrow.buyer {
+            return Err(ContractError::NotWinner {});
+        }
+        if escrow.disputed {
+            return Err(ContractError::EscrowDisputed {});
+        }
+        if env.block.time >= escrow.deadline {
+            return Err(ContractError::EscrowExpired {});
+        }
+
+        escrow.disputed = true;
+        ESCROW.save(deps.storage, &escrow)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "dispute")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn resolve_dispute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        buyer_amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let arbiter = config.escrow.ok_or(ContractError::NoEscrow {})?.arbiter;
+        if info.sender != arbiter {
+            return Err(ContractError::NotArbiter {
+                arbiter: arbiter.into(),
+            });
+        }
+
+        let mut escrow = pending_escrow(deps.storage)?;
+        if !escrow.disputed {
+            return Err(ContractError::EscrowNotDisputed {});
+        }
+        if buyer_amount > escrow.amount {
+            return Err(ContractError::InvalidSplit {
+                buyer_amount: buyer_amount.to_string(),
+                amount: escrow.amount.to_string(),
+            });
+        }
+
+        escrow.released = true;
+        ESCROW.save(deps.storage, &escrow)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "resolve_dispute")
+            .add_attribute("sender", info.sender.as_str());
+
+        if buyer_amount > Uint128::zero() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: escrow.buyer.to_string(),
+                amount: [Coin::new(buyer_amount.u128(), &config.denom)].to_vec(),
+            });
+        }
+
+        let seller_amount = escrow.amount - buyer_amount;
+        if seller_amount > Uint128::zero() {
+            let owner = load_owner(deps.storage)?;
+            resp = resp.add_message(BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: [Coin::new(seller_amount.u128(), &config.denom)].to_vec(),
+            });
+        }
+
+        Ok(resp)
+    }
+
+    pub fn claim_escrow(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let owner = ensure_owner(deps.storage, &info.sender)?;
+
+        let mut escrow = pending_escrow(deps.storage)?;
+        if escrow.disputed {
+            return Err(ContractError::EscrowDisputed {});
+        }
+        if env.block.time < escrow.deadline {
+            return Err(ContractError::EscrowNotExpired {});
+        }
+
+        escrow.released = true;
+        ESCROW.save(deps.storage, &escrow)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let bank_msg = BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: [Coin::new(escrow.amount.u128(), config.denom)].to_vec(),
+        };
+
+        let resp = Response::new()
+            .add_message(bank_msg)
+            .add_attribute("action", "claim_escrow")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    fn set_paused(
+        deps: DepsMut,
+        info: MessageInfo,
+        paused: bool,
+    ) -> Result<Response, ContractError> {
+        PAUSED.save(deps.storage, &paused)?;
+
+        let resp = Response::new()
+            .add_attribute("action", if paused { "pause" } else { "unpause" })
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn pause(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        ensure_role(
+            deps.storage,
+            &info.sender,
+            &[Role::Operator, Role::Guardian],
+        )?;
+        set_paused(deps, info, true)
+    }
+
+    // Guardians can only freeze the contract, lifting the pause is up to the operators
+    pub fn unpause(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        ensure_role(deps.storage, &info.sender, &[Role::Operator])?;
+        set_paused(deps, info, false)
+    }
+
+    pub fn cancel(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        ensure_role(deps.storage, &info.sender, &[Role::Operator])?;
+        cancel_bidding(deps.storage)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "cancel")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub(super) fn cancel_bidding(storage: &mut dyn Storage) -> Result<(), ContractError> {
+        if bidding_closed(storage)? {
+            return Err(ContractError::BiddingAlreadyClosed {});
+        }
+
+        CANCELLED.save(storage, &true)?;
+        Ok(())
+    }
+
+    pub fn grant_role(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        let address = validate_address(deps.api, &address)?;
+        ROLES.save(deps.storage, (role.as_str(), &address), &Empty {})?;
+
+        let resp = Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("role", role.as_str())
+            .add_attribute("address", address.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn revoke_role(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        role: Role,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        let address = validate_address(deps.api, &address)?;
+        ROLES.remove(deps.storage, (role.as_str(), &address));
+
+        let resp = Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("role", role.as_str())
+            .add_attribute("address", address.as_str());
+
+        Ok(resp)
+    }
+
+    /// Only affects bids placed afterwards, commissions already taken are kept as they are
+    pub fn update_commission(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        commission: Decimal,
+    ) -> Result<Response, ContractError> {
+        ensure_role(deps.storage, &info.sender, &[Role::FeeAdmin])?;
+
+        if commission > Decimal::one() {
+            return Err(ContractError::InvalidCommission {});
+        }
+
+        let mut config = CONFIG.load(deps.storage)?;
+        // The flat commission is never charged while a schedule is configured
+        if config.commission_schedule.is_some() {
+            return Err(ContractError::CommissionScheduled {});
+        }
+
+        config.commission = commission;
+        CONFIG.save(deps.storage, &config)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_commission")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("commission", commission.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn add_hook(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        let addr = validate_address(deps.api, &addr)?;
+        if HOOKS.has(deps.storage, &addr) {
+            return Err(ContractError::HookAlreadyRegistered { addr: addr.into() });
+        }
+        HOOKS.save(deps.storage, &addr, &Empty {})?;
+
+        let resp = Response::new()
+            .add_attribute("action", "add_hook")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("hook", addr.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn remove_hook(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        addr: String,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        let addr = validate_address(deps.api, &addr)?;
+        if !HOOKS.has(deps.storage, &addr) {
+            return Err(ContractError::HookNotRegistered { addr: addr.into() });
+        }
+        HOOKS.remove(deps.storage, &addr);
+
+        let resp = Response::new()
+            .add_attribute("action", "remove_hook")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("hook", addr.as_str());
+
+        Ok(resp)
+    }
+
+    /// Sent as a plain message, a failing transfer keeps the credit in place
+    pub fn claim_credit(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let credit = CREDITS
+            .may_load(deps.storage, &info.sender)?
+            .ok_or(ContractError::NoCredit {})?;
+        CREDITS.remove(deps.storage, &info.sender);
+
+        let config = CONFIG.load(deps.storage)?;
+        let bank_msg = BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: [Coin::new(credit.u128(), config.denom)].to_vec(),
+        };
+
+        let resp = Response::new()
+            .add_message(bank_msg)
+            .add_attribute("action", "claim_credit")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn deposit(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        pubkey: Binary,
+    ) -> Result<Response, ContractError> {
+        let valid_pubkey = matches!(
+            (pubkey.len(), pubkey.first()),
+            (33, Some(0x02 | 0x03)) | (65, Some(0x04))
+        );
+        if !valid_pubkey {
+            return Err(ContractError::InvalidPubkey {});
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let amount = info
+            .funds
+            .iter()
+            .find(|coin| coin.denom == config.denom)
+            .ok_or(ContractError::IncorrectDeposit {
+                denom: config.denom.clone(),
+            })?;
+
+        let deposit = DEPOSITS.may_load(deps.storage, &info.sender)?;
+        DEPOSITS.save(
+            deps.storage,
+            &info.sender,
+            &Deposit {
+                pubkey,
+                balance: deposit
+                    .as_ref()
+                    .map_or(Uint128::zero(), |deposit| deposit.balance)
+                    + amount.amount,
+                nonce: deposit.map_or(0, |deposit| deposit.nonce),
+            },
+        )?;
+
+        let resp = Response::new()
+            .add_attribute("action", "deposit")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("amount", amount.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn withdraw_deposit(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let mut deposit = DEPOSITS
+            .may_load(deps.storage, &info.sender)?
+            .filter(|deposit| !deposit.balance.is_zero())
+            .ok_or(ContractError::NoDeposit {})?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let amount = Coin::new(deposit.balance.u128(), config.denom);
+
+        // The pubkey and nonce are kept, so withdrawn intents cannot be replayed after a new deposit
+        deposit.balance = Uint128::zero();
+        DEPOSITS.save(deps.storage, &info.sender, &deposit)?;
+
+        let resp = Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![amount.clone()],
+            })
+            .add_attribute("action", "withdraw_deposit")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("amount", amount.to_string());
+
+        Ok(resp)
+    }
+
+    pub fn relay_bid(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        intent: BidIntent,
+        signature: Binary,
+        pubkey: Binary,
+    ) -> Result<Response, ContractError> {
+        let bidder = validate_address(deps.api, &intent.bidder)?;
+
+        if intent.auction != env.contract.address.as_str() {
+            return Err(ContractError::WrongAuction {});
+        }
+        if intent.expiry.is_expired(&env.block) {
+            return Err(ContractError::BidExpired {});
+        }
+
+        let mut deposit = DEPOSITS
+            .may_load(deps.storage, &bidder)?
+            .ok_or(ContractError::NoDeposit {})?;
+        if deposit.pubkey != pubkey {
+            return Err(ContractError::PubkeyMismatch {});
+        }
+
+        let hash = Sha256::digest(to_vec(&intent)?);
+        // Malformed signatures are rejected like wrong ones
+        let verified = deps
+            .api
+            .secp256k1_verify(&hash, &signature, &pubkey)
+            .unwrap_or(false);
+        if !verified {
+            return Err(ContractError::InvalidSignature {});
+        }
+
+        if intent.nonce != deposit.nonce {
+            return Err(ContractError::InvalidNonce {
+                expected: deposit.nonce,
+            });
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        if intent.amount > deposit.balance {
+            return Err(ContractError::InsufficientDeposit {
+                available: Coin::new(deposit.balance.u128(), config.denom).to_string(),
+            });
+        }
+
+        deposit.balance -= intent.amount;
+        deposit.nonce += 1;
+        DEPOSITS.save(deps.storage, &bidder, &deposit)?;
+
+        // The funds are already held by the contract, so the bid is made as if the bidder sent them
+        let bid_info = MessageInfo {
+            sender: bidder,
+            funds: vec![Coin::new(intent.amount.u128(), config.denom)],
+        };
+        let resp = bid(deps, env, bid_info, None)?.add_attribute("relayer", info.sender.as_str());
+
+        Ok(resp)
+    }
+
+    pub fn withdraw_fees(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        if recipient.is_some() && !config.fee_split.is_empty() {
+            return Err(ContractError::FeeRecipientWithSplit {});
+        }
+        let recipient = match recipient {
+            Some(recipient) => validate_address(deps.api, &recipient)?,
+            None => info.sender.clone(),
+        };
+
+        let mut fees = COLLECTED_FEES.load(deps.storage)?;
+        let available = fees.accrued - fees.withdrawn;
+        let amount = amount.unwrap_or(available);
+        if amount > available {
+            return Err(ContractError::InsufficientFees {
+                available: available.to_string(),
+            });
+        }
+
+        fees.withdrawn += amount;
+        COLLECTED_FEES.save(deps.storage, &fees)?;
+
+        let shares = if config.fee_split.is_empty() {
+            vec![(recipient, amount)]
+        } else {
+            split_fees(amount, &config.fee_split)
+        };
+
+        let bank_msgs =
+            shares
+                .iter()
+                .filter(|(_, share)| !share.is_zero())
+                .map(|(recipient, share)| BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: [Coin::new(share.u128(), &config.denom)].to_vec(),
+                });
+
+        let resp = Response::new()
+            .add_messages(bank_msgs)
+            .add_attribute("action", "withdraw_fees")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("amount", amount.to_string());
+
+        Ok(resp)
+    }
+
+    /// Shares are rounded down and the remainder goes to the first beneficiary, so the whole
+    /// amount is always paid out
+    fn split_fees(amount: Uint128, fee_split: &[(Addr, Decimal)]) -> Vec<(Addr, Uint128)> {
+        let mut shares: Vec<_> = fee_split
+            .iter()
+            .map(|(beneficiary, weight)| (beneficiary.clone(), amount * *weight))
+            .collect();
+
+        let paid: Uint128 = shares.iter().map(|(_, share)| *share).sum();
+        shares[0].1 += amount - paid;
+
+        shares
+    }
+
+    pub fn update_fee_split(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        fee_split: Vec<(String, Decimal)>,
+    ) -> Result<Response, ContractError> {
+        ensure_owner(deps.storage, &info.sender)?;
+
+        let fee_split = validate_fee_split(deps.api, fee_split)?;
+        CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+            config.fee_split = fee_split;
+            Ok(config)
+        })?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_fee_split")
+            .add_attribute("sender", info.sender.as_str());
+
+        Ok(resp)
+    }
+}
+
+/// Governance interventions, bypassing the roles and the pause
+pub mod sudo {
+    use cosmwasm_std::{DepsMut, Env, Response};
+
+    use crate::{
+        error::ContractError,
+        state::{OWNER, PENDING_OWNER},
+    };
+
+    use super::{
+        exec::{cancel_bidding, close_bidding, ensure_not_bidder},
+        validate_address,
+    };
+
+    pub fn force_close(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+        let resp = Response::new().add_attribute("action", "force_close");
+        close_bidding(deps, &env, resp)
+    }
+
+    pub fn force_cancel(deps: DepsMut, _env: Env) -> Result<Response, ContractError> {
+        cancel_bidding(deps.storage)?;
+
+        let resp = Response::new().add_attribute("action", "force_cancel");
+        Ok(resp)
+    }
+
+    /// Also restores an owner after the ownership was renounced
+    pub fn update_owner(
+        deps: DepsMut,
+        _env: Env,
+        owner: String,
+    ) -> Result<Response, ContractError> {
+        let owner = validate_address(deps.api, &owner)?;
+        ensure_not_bidder(deps.storage, &owner)?;
+
+        OWNER.save(deps.storage, &owner)?;
+        PENDING_OWNER.remove(deps.storage);
+
+        let resp = Response::new()
+            .add_attribute("action", "update_owner")
+            .add_attribute("owner", owner.as_str());
+
+        Ok(resp)
     }
 }
@@ -12,12 +12,18 @@ pub enum ContractError {
     #[error("Owner cannot bid")]
     OwnerCannotBid {},
 
+    #[error("Bidders cannot become the owner")]
+    BidderCannotOwn {},
+
     #[error("Insufficient Bid - the bid {bid} is lower than the highest bid {highest_bid}")]
     InsufficientBid { bid: String, highest_bid: String },
 
     #[error("Incorrect Bid - the bid should be done using the native token")]
     IncorrectBid {},
 
+    #[error("The bidding has not started yet")]
+    BiddingNotStarted {},
+
     #[error("The bidding is already closed")]
     BiddingAlreadyClosed {},
 
@@ -29,4 +35,157 @@ pub enum ContractError {
 
     #[error("Winner cannot retract")]
     WinnerCannotRetract {},
+
+    #[error("The contract has no owner")]
+    NoOwner {},
+
+    #[error("There is no pending ownership transfer")]
+    NoPendingOwner {},
+
+    #[error("Only the pending owner {pending_owner} can accept the ownership")]
+    NotPendingOwner { pending_owner: String },
+
+    #[error("The ownership proposal has expired")]
+    OwnershipProposalExpired {},
+
+    #[error("Invalid address {address}")]
+    InvalidAddress { address: String },
+
+    #[error("The denom cannot be empty")]
+    InvalidDenom {},
+
+    #[error("The commission cannot exceed 100%")]
+    InvalidCommission {},
+
+    #[error("The end time must be later than the start time")]
+    InvalidTimeWindow {},
+
+    #[error("The address {address} is not permitted to bid")]
+    NotPermitted { address: String },
+
+    #[error("The address {address} is not eligible to bid")]
+    NotEligible { address: String },
+
+    #[error("This bidding does not require a bond")]
+    BondNotRequired {},
+
+    #[error("Incorrect Bond - the bond of {bond} should be sent using the native token")]
+    IncorrectBond { bond: String },
+
+    #[error("The bond cannot be zero")]
+    InvalidBond {},
+
+    #[error("The address is already registered")]
+    AlreadyRegistered {},
+
+    #[error("The address has to register with a bond before bidding")]
+    NotRegistered {},
+
+    #[error("Only the winner can call it")]
+    NotWinner {},
+
+    #[error("There is no pending settlement")]
+    NoSettlementPending {},
+
+    #[error("The settlement period has expired")]
+    SettlementExpired {},
+
+    #[error("The settlement period has not expired yet")]
+    SettlementNotExpired {},
+
+    #[error("The settlement is still pending")]
+    SettlementPending {},
+
+    #[error("Provisional settlements are advanced with AdvanceSettlement")]
+    ProvisionalSettlement {},
+
+    #[error("The bidding has no provisional settlement")]
+    NotProvisionalSettlement {},
+
+    #[error("There are no funds held in escrow")]
+    NoEscrow {},
+
+    #[error("Only the arbiter {arbiter} can call it")]
+    NotArbiter { arbiter: String },
+
+    #[error("The escrow is disputed")]
+    EscrowDisputed {},
+
+    #[error("The escrow is not disputed")]
+    EscrowNotDisputed {},
+
+    #[error("The escrow period has expired")]
+    EscrowExpired {},
+
+    #[error("The escrow period has not expired yet")]
+    EscrowNotExpired {},
+
+    #[error("The buyer amount {buyer_amount} exceeds the escrowed {amount}")]
+    InvalidSplit { buyer_amount: String, amount: String },
+
+    #[error("The contract is paused")]
+    Paused {},
+
+    #[error("Hook {addr} is already registered")]
+    HookAlreadyRegistered { addr: String },
+
+    #[error("Hook {addr} is not registered")]
+    HookNotRegistered { addr: String },
+
+    #[error("Only {available} in fees can be withdrawn")]
+    InsufficientFees { available: String },
+
+    #[error("Fee split weights have to be positive and sum to 1")]
+    InvalidFeeSplit {},
+
+    #[error("Fees are paid to the fee split, no recipient can be given")]
+    FeeRecipientWithSplit {},
+
+    #[error("Collected fees have to be withdrawn first")]
+    FeesPending {},
+
+    #[error("Commission tiers have to start at zero with increasing thresholds and non-increasing rates")]
+    InvalidCommissionSchedule {},
+
+    #[error("The commission follows the commission schedule")]
+    CommissionScheduled {},
+
+    #[error("Invalid referral share")]
+    InvalidReferralShare {},
+
+    #[error("Bidders cannot refer themselves")]
+    SelfReferral {},
+
+    #[error("There is no credit to claim")]
+    NoCredit {},
+
+    #[error("Deposits have to be made in {denom}")]
+    IncorrectDeposit { denom: String },
+
+    #[error("Invalid secp256k1 public key")]
+    InvalidPubkey {},
+
+    #[error("There is no deposit to withdraw")]
+    NoDeposit {},
+
+    #[error("Only {available} is deposited")]
+    InsufficientDeposit { available: String },
+
+    #[error("The public key is not the one bound to the bidder's deposit")]
+    PubkeyMismatch {},
+
+    #[error("Invalid bid signature")]
+    InvalidSignature {},
+
+    #[error("The bid was signed for another auction")]
+    WrongAuction {},
+
+    #[error("The signed bid expired")]
+    BidExpired {},
+
+    #[error("Invalid nonce, expected {expected}")]
+    InvalidNonce { expected: u64 },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
 }
@@ -0,0 +1,167 @@
+//! Typed events emitted next to the plain `action`/`sender` attributes, so indexers don't have to
+//! guess what every attribute means. The chain prefixes the event types with `wasm-`.
+
+use cosmwasm_std::{Addr, Coin, Event, StdError, StdResult, Uint128};
+
+pub const BID_EVENT: &str = "bid";
+pub const CLOSE_EVENT: &str = "close";
+pub const RETRACT_EVENT: &str = "retract";
+
+pub const BIDDER: &str = "bidder";
+pub const AMOUNT: &str = "amount";
+pub const COMMISSION: &str = "commission";
+pub const TOTAL: &str = "total";
+pub const PREVIOUS_LEADER: &str = "previous_leader";
+pub const LEADER: &str = "leader";
+pub const WINNER: &str = "winner";
+pub const WINNING_BID: &str = "winning_bid";
+pub const RECEIVER: &str = "receiver";
+
+/// A bid accepted by `Bid {}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidEvent {
+    pub bidder: Addr,
+    /// Funds sent with this bid
+    pub amount: Coin,
+    /// Commission taken from `amount`
+    pub commission: Coin,
+    /// Bidder's total bid after this one
+    pub total: Coin,
+    /// Highest bidder before this bid, `None` for the first bid
+    pub previous_leader: Option<Addr>,
+    pub leader: Addr,
+}
+
+/// The bidding was closed, `winner` is `None` if nobody bid
+#[derive(Clone, Debug, PartialEq)]
+pub struct CloseEvent {
+    pub winner: Option<Addr>,
+    pub winning_bid: Option<Coin>,
+}
+
+/// A bid and bond returned by `Retract {}`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetractEvent {
+    pub bidder: Addr,
+    pub receiver: Addr,
+    /// Refunded funds, net of the commission unless it is refunded
+    pub amount: Coin,
+}
+
+impl From<BidEvent> for Event {
+    fn from(event: BidEvent) -> Self {
+        let mut ev = Event::new(BID_EVENT)
+            .add_attribute(BIDDER, event.bidder)
+            .add_attribute(AMOUNT, event.amount.to_string())
+            .add_attribute(COMMISSION, event.commission.to_string())
+            .add_attribute(TOTAL, event.total.to_string());
+
+        if let Some(previous_leader) = event.previous_leader {
+            ev = ev.add_attribute(PREVIOUS_LEADER, previous_leader);
+        }
+
+        ev.add_attribute(LEADER, event.leader)
+    }
+}
+
+impl From<CloseEvent> for Event {
+    fn from(event: CloseEvent) -> Self {
+        let mut ev = Event::new(CLOSE_EVENT);
+
+        if let Some(winner) = event.winner {
+            ev = ev.add_attribute(WINNER, winner);
+        }
+        if let Some(winning_bid) = event.winning_bid {
+            ev = ev.add_attribute(WINNING_BID, winning_bid.to_string());
+        }
+
+        ev
+    }
+}
+
+impl From<RetractEvent> for Event {
+    fn from(event: RetractEvent) -> Self {
+        Event::new(RETRACT_EVENT)
+            .add_attribute(BIDDER, event.bidder)
+            .add_attribute(RECEIVER, event.receiver)
+            .add_attribute(AMOUNT, event.amount.to_string())
+    }
+}
+
+impl BidEvent {
+    pub fn parse(event: &Event) -> StdResult<Self> {
+        ensure_type(event, BID_EVENT)?;
+
+        Ok(BidEvent {
+            bidder: Addr::unchecked(attribute(event, BIDDER)?),
+            amount: parse_coin(attribute(event, AMOUNT)?)?,
+            commission: parse_coin(attribute(event, COMMISSION)?)?,
+            total: parse_coin(attribute(event, TOTAL)?)?,
+            previous_leader: optional_attribute(event, PREVIOUS_LEADER).map(Addr::unchecked),
+            leader: Addr::unchecked(attribute(event, LEADER)?),
+        })
+    }
+}
+
+impl CloseEvent {
+    pub fn parse(event: &Event) -> StdResult<Self> {
+        ensure_type(event, CLOSE_EVENT)?;
+
+        Ok(CloseEvent {
+            winner: optional_attribute(event, WINNER).map(Addr::unchecked),
+            winning_bid: optional_attribute(event, WINNING_BID)
+                .map(parse_coin)
+                .transpose()?,
+        })
+    }
+}
+
+impl RetractEvent {
+    pub fn parse(event: &Event) -> StdResult<Self> {
+        ensure_type(event, RETRACT_EVENT)?;
+
+        Ok(RetractEvent {
+            bidder: Addr::unchecked(attribute(event, BIDDER)?),
+            receiver: Addr::unchecked(attribute(event, RECEIVER)?),
+            amount: parse_coin(attribute(event, AMOUNT)?)?,
+        })
+    }
+}
+
+/// Accepts both the type set by the contract and the `wasm-` prefixed one seen on chain
+fn ensure_type(event: &Event, ty: &str) -> StdResult<()> {
+    if event.ty == ty || event.ty.strip_prefix("wasm-") == Some(ty) {
+        return Ok(());
+    }
+
+    Err(StdError::parse_err(
+        ty,
+        format!("unexpected event type {}", event.ty),
+    ))
+}
+
+fn optional_attribute<'a>(event: &'a Event, key: &str) -> Option<&'a str> {
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+}
+
+fn attribute<'a>(event: &'a Event, key: &str) -> StdResult<&'a str> {
+    optional_attribute(event, key)
+        .ok_or_else(|| StdError::parse_err(&event.ty, format!("missing attribute {}", key)))
+}
+
+/// Parses coins formatted as `{amount}{denom}`, like `Coin`'s `Display` does
+fn parse_coin(value: &str) -> StdResult<Coin> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| StdError::parse_err("Coin", format!("missing denom in {}", value)))?;
+    let (amount, denom) = value.split_at(split);
+
+    Ok(Coin {
+        amount: amount.parse::<Uint128>()?,
+        denom: denom.to_string(),
+    })
+}
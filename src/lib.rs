@@ -1,12 +1,13 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
 };
 
 use error::ContractError;
-use msg::{InstantiateMsg, QueryMsg, ExecMsg};
+use msg::{InstantiateMsg, QueryMsg, ExecMsg, SudoMsg};
 
 mod contract;
 pub mod error;
+pub mod events;
 pub mod msg;
 #[cfg(any(test, feature = "tests"))]
 pub mod multitest;
@@ -15,21 +16,56 @@ mod state;
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
-    contract::instantiate(deps, info, msg)
+) -> Result<Response, ContractError> {
+    contract::instantiate(deps, env, info, msg)
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     use msg::QueryMsg::*;
 
     match msg {
         HighestBid {} => to_binary(&contract::query::highest_bid(deps)?),
         AddressBid {address} => to_binary(&contract::query::address_bid(deps, address)?),
         Winner {} => to_binary(&contract::query::winner(deps)?),
+        AllBids { start_after, limit } => {
+            to_binary(&contract::query::all_bids(deps, start_after, limit)?)
+        }
+        TopBids { limit } => to_binary(&contract::query::top_bids(deps, limit)?),
+        BidHistory {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&contract::query::bid_history(
+            deps,
+            address,
+            start_after,
+            limit,
+        )?),
+        Status {} => to_binary(&contract::query::status(deps, env)?),
+        Config {} => to_binary(&contract::query::config(deps)?),
+        Ownership {} => to_binary(&contract::query::ownership(deps)?),
+        Roles {} => to_binary(&contract::query::roles(deps)?),
+        Hooks {} => to_binary(&contract::query::hooks(deps)?),
+        Credit { address } => to_binary(&contract::query::credit(deps, address)?),
+        Deposit { address } => to_binary(&contract::query::deposit(deps, address)?),
+        Fees {} => to_binary(&contract::query::fees(deps)?),
+        Referrals { referrer } => to_binary(&contract::query::referrals(deps, referrer)?),
+        SimulateBid { address, funds } => {
+            to_binary(&contract::query::simulate_bid(deps, env, address, funds)?)
+        }
+        Allowlist { start_after, limit } => {
+            to_binary(&contract::query::allowlist(deps, start_after, limit)?)
+        }
+        Denylist { start_after, limit } => {
+            to_binary(&contract::query::denylist(deps, start_after, limit)?)
+        }
+        Bond { address } => to_binary(&contract::query::bond(deps, address)?),
+        Settlement {} => to_binary(&contract::query::settlement(deps)?),
+        Escrow {} => to_binary(&contract::query::escrow(deps)?),
     }
 }
 
@@ -43,8 +79,71 @@ pub fn execute(
     use msg::ExecMsg::*;
 
     match msg {
-        Bid {} => contract::exec::bid(deps, env, info).map_err(ContractError::from),
-        Close {} => contract::exec::close(deps, env, info).map_err(ContractError::from),
-        Retract { receiver } => contract::exec::retract(deps, env, info, receiver).map_err(ContractError::from),
+        Bid { referrer } => contract::exec::bid(deps, env, info, referrer),
+        Close {} => contract::exec::close(deps, env, info),
+        Retract { receiver } => contract::exec::retract(deps, env, info, receiver),
+        ProposeOwner { new_owner, expiry } => {
+            contract::exec::propose_owner(deps, env, info, new_owner, expiry)
+        }
+        AcceptOwnership {} => contract::exec::accept_ownership(deps, env, info),
+        RenounceOwnership {} => contract::exec::renounce_ownership(deps, env, info),
+        UpdateAllowlist { add, remove } => {
+            contract::exec::update_allowlist(deps, env, info, add, remove)
+        }
+        UpdateDenylist { add, remove } => {
+            contract::exec::update_denylist(deps, env, info, add, remove)
+        }
+        Register {} => contract::exec::register(deps, env, info),
+        ConfirmPurchase {} => contract::exec::confirm_purchase(deps, env, info),
+        SlashBond {} => contract::exec::slash_bond(deps, env, info),
+        AdvanceSettlement {} => contract::exec::advance_settlement(deps, env, info),
+        ConfirmDelivery {} => contract::exec::confirm_delivery(deps, env, info),
+        Dispute {} => contract::exec::dispute(deps, env, info),
+        ResolveDispute { buyer_amount } => {
+            contract::exec::resolve_dispute(deps, env, info, buyer_amount)
+        }
+        ClaimEscrow {} => contract::exec::claim_escrow(deps, env, info),
+        Pause {} => contract::exec::pause(deps, env, info),
+        Unpause {} => contract::exec::unpause(deps, env, info),
+        Cancel {} => contract::exec::cancel(deps, env, info),
+        GrantRole { role, address } => contract::exec::grant_role(deps, env, info, role, address),
+        RevokeRole { role, address } => {
+            contract::exec::revoke_role(deps, env, info, role, address)
+        }
+        UpdateCommission { commission } => {
+            contract::exec::update_commission(deps, env, info, commission)
+        }
+        AddHook { addr } => contract::exec::add_hook(deps, env, info, addr),
+        RemoveHook { addr } => contract::exec::remove_hook(deps, env, info, addr),
+        ClaimCredit {} => contract::exec::claim_credit(deps, env, info),
+        Deposit { pubkey } => contract::exec::deposit(deps, env, info, pubkey),
+        WithdrawDeposit {} => contract::exec::withdraw_deposit(deps, env, info),
+        RelayBid {
+            bid,
+            signature,
+            pubkey,
+        } => contract::exec::relay_bid(deps, env, info, bid, signature, pubkey),
+        WithdrawFees { amount, recipient } => {
+            contract::exec::withdraw_fees(deps, env, info, amount, recipient)
+        }
+        UpdateFeeSplit { fee_split } => {
+            contract::exec::update_fee_split(deps, env, info, fee_split)
+        }
+    }
+}
+
+#[entry_point]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    contract::reply(deps, env, msg)
+}
+
+#[entry_point]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    use msg::SudoMsg::*;
+
+    match msg {
+        ForceClose {} => contract::sudo::force_close(deps, env),
+        ForceCancel {} => contract::sudo::force_cancel(deps, env),
+        UpdateOwner { owner } => contract::sudo::update_owner(deps, env, owner),
     }
 }
@@ -1,9 +1,108 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Timestamp, Uint128};
+use cw_utils::Expiration;
 
 #[cw_serde]
+#[derive(Default)]
 pub struct InstantiateMsg {
-    pub owner: Option<Addr>,
+    pub owner: Option<String>,
+    /// Native token accepted for bids, `atom` if not provided
+    pub denom: Option<String>,
+    /// Share of every bid taken as commission, 10% if not provided
+    pub commission: Option<Decimal>,
+    /// Bids are rejected before this time
+    pub start_time: Option<Timestamp>,
+    /// Bids are rejected from this time on
+    pub end_time: Option<Timestamp>,
+    /// Free-form description of the off-chain lot
+    pub description: Option<String>,
+    /// Restricts bidding to the given addresses, open to everyone if not provided
+    pub allowlist: Option<Vec<String>>,
+    /// Contract consulted with `RegistryQueryMsg::IsEligible` before accepting a bid
+    pub eligibility_registry: Option<String>,
+    /// Bond bidders have to post with `Register {}` before bidding
+    pub bond: Option<BondConfig>,
+    /// When set, `close` only designates a provisional winner who has this many seconds to
+    /// `ConfirmPurchase {}` before the lot is offered to the next-highest bidder
+    pub provisional_window: Option<u64>,
+    /// Holds the winning bid in the contract until the winner confirms the delivery
+    pub escrow: Option<EscrowConfig>,
+    /// Address granted the `Role::Guardian` role
+    pub guardian: Option<String>,
+    /// Whether pausing also blocks `Retract {}`
+    #[serde(default)]
+    pub pause_retract: bool,
+    /// Beneficiaries sharing the withdrawn fees by weight, the weights have to sum to 1. Fees go
+    /// to the `WithdrawFees` recipient if not provided.
+    pub fee_split: Option<Vec<(String, Decimal)>>,
+    /// Share of a referred bidder's commissions credited to their referrer, none if not provided
+    pub referral_share: Option<Decimal>,
+    /// Replaces the flat `commission` when provided
+    pub commission_schedule: Option<CommissionSchedule>,
+    /// Holds the commissions until the close, only the winner's is kept and the losing bidders
+    /// get theirs back with `Retract {}`
+    #[serde(default)]
+    pub refund_commissions: bool,
+}
+
+/// Commission rates decreasing with the bid size or the auction age. The first tier has to start
+/// at zero, the thresholds have to increase and the rates must not.
+#[cw_serde]
+pub enum CommissionSchedule {
+    /// Rates by the bidder's total bid, each applying to the part of the total within its tier
+    Volume { tiers: Vec<VolumeTier> },
+    /// Rates by the seconds elapsed since the auction started
+    Time { tiers: Vec<TimeTier> },
+}
+
+#[cw_serde]
+pub struct VolumeTier {
+    pub from: Uint128,
+    pub rate: Decimal,
+}
+
+#[cw_serde]
+pub struct TimeTier {
+    pub after: u64,
+    pub rate: Decimal,
+}
+
+#[cw_serde]
+pub struct EscrowConfig {
+    /// Address resolving disputes between the winner and the owner
+    pub arbiter: String,
+    /// Seconds after which the owner can claim undisputed funds
+    pub timeout: u64,
+}
+
+#[cw_serde]
+pub struct BondConfig {
+    /// Bond amount in the bidding denom
+    pub amount: Uint128,
+    /// Seconds the winner has after closing to confirm the purchase before the bond is slashed
+    pub settlement_period: u64,
+}
+
+/// Roles the owner can grant on top of the ownership itself, which keeps the proceeds
+#[cw_serde]
+#[derive(Copy)]
+pub enum Role {
+    /// Can close, cancel, pause and unpause the auction
+    Operator,
+    /// Can change the commission
+    FeeAdmin,
+    /// Can only pause the contract
+    Guardian,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Operator => "operator",
+            Role::FeeAdmin => "fee_admin",
+            Role::Guardian => "guardian",
+        }
+    }
 }
 
 #[cw_serde]
@@ -15,13 +114,199 @@ pub enum QueryMsg {
     AddressBid { address: String },
     #[returns(WinnerResp)]
     Winner {},
+    #[returns(AllBidsResp)]
+    AllBids {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(TopBidsResp)]
+    TopBids { limit: Option<u32> },
+    #[returns(BidHistoryResp)]
+    BidHistory {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(StatusResp)]
+    Status {},
+    #[returns(ConfigResp)]
+    Config {},
+    #[returns(OwnershipResp)]
+    Ownership {},
+    #[returns(RolesResp)]
+    Roles {},
+    #[returns(HooksResp)]
+    Hooks {},
+    #[returns(CreditResp)]
+    Credit { address: String },
+    #[returns(DepositResp)]
+    Deposit { address: String },
+    #[returns(FeesResp)]
+    Fees {},
+    #[returns(ReferralsResp)]
+    Referrals { referrer: String },
+    /// Outcome of `address` bidding `funds` now, without executing the bid
+    #[returns(SimulateBidResp)]
+    SimulateBid { address: String, funds: Vec<Coin> },
+    #[returns(AddressListResp)]
+    Allowlist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(AddressListResp)]
+    Denylist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(BondResp)]
+    Bond { address: String },
+    #[returns(SettlementResp)]
+    Settlement {},
+    #[returns(EscrowResp)]
+    Escrow {},
 }
 
 #[cw_serde]
 pub enum ExecMsg {
-    Bid {},
+    /// `referrer` is only recorded on the bidder's first bid
+    Bid {
+        referrer: Option<String>,
+    },
     Close {},
-    Retract { receiver: Option<String> },
+    /// Available once the bidding is closed, cancelled or past its end time
+    Retract {
+        receiver: Option<String>,
+    },
+    ProposeOwner {
+        new_owner: String,
+        expiry: Option<Expiration>,
+    },
+    AcceptOwnership {},
+    RenounceOwnership {},
+    UpdateAllowlist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    UpdateDenylist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    Register {},
+    ConfirmPurchase {},
+    SlashBond {},
+    AdvanceSettlement {},
+    ConfirmDelivery {},
+    Dispute {},
+    ResolveDispute {
+        buyer_amount: Uint128,
+    },
+    ClaimEscrow {},
+    Pause {},
+    Unpause {},
+    Cancel {},
+    GrantRole {
+        role: Role,
+        address: String,
+    },
+    RevokeRole {
+        role: Role,
+        address: String,
+    },
+    /// Fails when a `commission_schedule` is configured
+    UpdateCommission {
+        commission: Decimal,
+    },
+    /// Registers a contract notified with `BiddingHookMsg`, with 500k gas for every notification
+    AddHook {
+        addr: String,
+    },
+    RemoveHook {
+        addr: String,
+    },
+    ClaimCredit {},
+    /// Withdraws all available fees if `amount` is not provided, to the sender if `recipient` is
+    /// not provided
+    WithdrawFees {
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+    },
+    /// Owner only. An empty split pays the fees to the `WithdrawFees` recipient again
+    UpdateFeeSplit {
+        fee_split: Vec<(String, Decimal)>,
+    },
+    /// Adds the sent funds to the sender's deposit and binds `pubkey`, the compressed or
+    /// uncompressed secp256k1 key signing the sender's relayed bids
+    Deposit {
+        pubkey: Binary,
+    },
+    WithdrawDeposit {},
+    /// Bids on behalf of `bid.bidder` from their deposit. `signature` is the 64 bytes secp256k1
+    /// signature of the sha256 hash of the JSON serialized `bid`
+    RelayBid {
+        bid: BidIntent,
+        signature: Binary,
+        pubkey: Binary,
+    },
+}
+
+/// Bid signed off-chain by the bidder and submitted by a relayer
+#[cw_serde]
+pub struct BidIntent {
+    /// Address of the bidding contract, so the intent cannot be replayed on another auction
+    pub auction: String,
+    pub bidder: String,
+    pub amount: Uint128,
+    /// Has to be the bidder's next nonce, as returned by `QueryMsg::Deposit`
+    pub nonce: u64,
+    pub expiry: Expiration,
+}
+
+/// Notification sent to every registered hook, wrapped in `BiddingHookExecuteMsg`
+#[cw_serde]
+pub enum BiddingHookMsg {
+    /// `bidder` became the highest bidder with `total`
+    NewHighestBid { bidder: Addr, total: Coin },
+    /// `bidder` with `total` lost the lead to `outbid_by`
+    Outbid {
+        bidder: Addr,
+        total: Coin,
+        outbid_by: Addr,
+    },
+    /// The bidding was closed, `winner` is `None` if nobody bid
+    Closed {
+        winner: Option<Addr>,
+        winning_bid: Option<Coin>,
+    },
+}
+
+/// Execute message hook contracts have to accept, as `{"bidding_hook": {...}}`
+#[cw_serde]
+pub enum BiddingHookExecuteMsg {
+    BiddingHook(BiddingHookMsg),
+}
+
+/// Messages reserved to the chain governance
+#[cw_serde]
+pub enum SudoMsg {
+    /// Closes the bidding as `Close {}` would, even while paused
+    ForceClose {},
+    /// Cancels the auction as `Cancel {}` would
+    ForceCancel {},
+    /// Replaces the owner and drops any pending ownership transfer
+    UpdateOwner { owner: String },
+}
+
+/// Query interface an eligibility registry contract has to implement
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum RegistryQueryMsg {
+    #[returns(IsEligibleResp)]
+    IsEligible { address: String },
+}
+
+#[cw_serde]
+pub struct IsEligibleResp {
+    pub eligible: bool,
 }
 
 #[cw_serde]
@@ -32,11 +317,199 @@ pub struct HighestBidResp {
 
 #[cw_serde]
 pub struct AddressBidResp {
+    /// `None` if the address has no bid on record
+    pub bid: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct WinnerInfo {
+    pub address: Addr,
     pub bid: Coin,
 }
 
 #[cw_serde]
 pub struct WinnerResp {
+    /// `None` until the bidding is closed
+    pub winner: Option<WinnerInfo>,
+}
+
+#[cw_serde]
+pub struct BidInfo {
     pub address: Addr,
     pub bid: Coin,
+    pub net_bid: Coin,
+    pub commission: Coin,
+}
+
+#[cw_serde]
+pub struct AllBidsResp {
+    pub bids: Vec<BidInfo>,
+    pub total_count: u64,
+}
+
+#[cw_serde]
+pub struct TopBidsResp {
+    pub bids: Vec<BidInfo>,
+}
+
+#[cw_serde]
+pub struct BidHistoryEntry {
+    pub id: u64,
+    pub timestamp: Timestamp,
+    pub height: u64,
+    pub amount: Coin,
+    pub commission: Coin,
+    pub total: Coin,
+}
+
+#[cw_serde]
+pub struct BidHistoryResp {
+    pub bids: Vec<BidHistoryEntry>,
+}
+
+#[cw_serde]
+pub enum AuctionStatus {
+    /// The configured start time has not been reached yet
+    NotStarted,
+    /// Bids are accepted
+    Open,
+    /// Bidding is over, either closed by the owner or past the end time
+    Closed,
+    /// The provisional winner has not confirmed the purchase yet
+    AwaitingConfirmation,
+    /// Every bidder offered the lot failed to confirm the purchase
+    Unsold,
+    /// The winning bid is held in escrow until the delivery is confirmed
+    InEscrow,
+    /// The auction was cancelled and every bid can be retracted
+    Cancelled,
+    /// The winning bid has been settled with the owner
+    Settled,
+}
+
+#[cw_serde]
+pub struct StatusResp {
+    pub status: AuctionStatus,
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct ConfigResp {
+    pub owner: Option<Addr>,
+    pub denom: String,
+    pub commission: Decimal,
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+    pub description: Option<String>,
+    pub allowlist_enabled: bool,
+    pub eligibility_registry: Option<Addr>,
+    pub bond: Option<BondConfig>,
+    pub provisional_window: Option<u64>,
+    pub escrow: Option<EscrowConfig>,
+    pub pause_retract: bool,
+    pub fee_split: Vec<(Addr, Decimal)>,
+    pub referral_share: Decimal,
+    pub commission_schedule: Option<CommissionSchedule>,
+    pub refund_commissions: bool,
+}
+
+#[cw_serde]
+pub struct OwnershipResp {
+    /// `None` once the ownership has been renounced
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+    pub pending_expiry: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct RolesResp {
+    pub owner: Option<Addr>,
+    pub operators: Vec<Addr>,
+    pub fee_admins: Vec<Addr>,
+    pub guardians: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct HooksResp {
+    pub hooks: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct DepositResp {
+    pub balance: Coin,
+    pub pubkey: Option<Binary>,
+    /// Nonce the next relayed bid has to be signed with
+    pub nonce: u64,
+}
+
+#[cw_serde]
+pub struct CreditResp {
+    /// Payouts to the address that failed and can be claimed with `ClaimCredit {}`
+    pub credit: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct FeesResp {
+    /// Commissions taken since the instantiation, net of referral rewards
+    pub accrued: Coin,
+    pub withdrawn: Coin,
+    pub available: Coin,
+}
+
+#[cw_serde]
+pub struct ReferralsResp {
+    /// Bidders attributed to the referrer
+    pub bidders: u64,
+    /// Rewards credited to the referrer, claimable with `ClaimCredit {}`
+    pub rewards: Coin,
+}
+
+#[cw_serde]
+pub enum SimulateBidResp {
+    Accepted {
+        commission: Coin,
+        /// Bidder's total bid after the simulated one, which becomes the highest bid
+        total: Coin,
+    },
+    /// `error` is the message of the `ContractError` a real bid would fail with
+    Rejected { error: String },
+}
+
+#[cw_serde]
+pub struct AddressListResp {
+    pub addresses: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct BondResp {
+    /// `None` if the address has not registered or already got the bond back
+    pub bond: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct SettlementInfo {
+    pub deadline: Timestamp,
+    pub confirmed: bool,
+    pub slashed: bool,
+}
+
+#[cw_serde]
+pub struct SettlementResp {
+    /// `None` unless a bond is required and the bidding was closed with a winning bid
+    pub settlement: Option<SettlementInfo>,
+}
+
+#[cw_serde]
+pub struct EscrowInfo {
+    pub buyer: Addr,
+    pub amount: Coin,
+    pub deadline: Timestamp,
+    pub disputed: bool,
+    pub released: bool,
+}
+
+#[cw_serde]
+pub struct EscrowResp {
+    /// `None` unless escrow is configured and the winning bid was paid into it
+    pub escrow: Option<EscrowInfo>,
 }
@@ -1,20 +1,30 @@
-use cosmwasm_std::{Addr, Coin, StdResult};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, StdResult, Uint128};
 use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_utils::Expiration;
 
 use crate::{
     error::ContractError,
-    msg::{AddressBidResp, ExecMsg, HighestBidResp, InstantiateMsg, QueryMsg, WinnerResp},
+    msg::{
+        AddressBidResp, AddressListResp, AllBidsResp, BidHistoryResp, BidIntent, BondResp, ConfigResp, CreditResp, DepositResp, EscrowResp, ExecMsg, FeesResp, HighestBidResp, HooksResp,
+        InstantiateMsg, OwnershipResp, QueryMsg, ReferralsResp, SimulateBidResp, Role, RolesResp, SettlementResp, StatusResp, SudoMsg,
+        TopBidsResp, WinnerResp,
+    },
 };
-use crate::{execute, instantiate, query};
+use crate::{execute, instantiate, query, reply, sudo};
 
+pub mod hook;
+pub mod registry;
 #[cfg(test)]
 mod tests;
 
+#[derive(Debug)]
 pub struct BiddingContract(Addr);
 
 impl BiddingContract {
     pub fn store_code(app: &mut App) -> u64 {
-        let contract = ContractWrapper::new(execute, instantiate, query);
+        let contract = ContractWrapper::new(execute, instantiate, query)
+            .with_sudo(sudo)
+            .with_reply(reply);
         app.store_code(Box::new(contract))
     }
 
@@ -26,11 +36,14 @@ impl BiddingContract {
         admin: Option<&Addr>,
         label: &str,
         owner: Option<Addr>,
-    ) -> StdResult<BiddingContract> {
+    ) -> Result<BiddingContract, ContractError> {
         app.instantiate_contract(
             code_id,
             sender.clone(),
-            &InstantiateMsg { owner },
+            &InstantiateMsg {
+                owner: owner.map(Addr::into_string),
+                ..Default::default()
+            },
             &[],
             label,
             admin.map(Addr::to_string),
@@ -39,18 +52,52 @@ impl BiddingContract {
         .map(BiddingContract)
     }
 
+    #[track_caller]
+    pub fn instantiate_with_msg(
+        app: &mut App,
+        code_id: u64,
+        sender: &Addr,
+        label: &str,
+        msg: &InstantiateMsg,
+    ) -> Result<BiddingContract, ContractError> {
+        app.instantiate_contract(code_id, sender.clone(), msg, &[], label, None)
+            .map_err(|err| err.downcast().unwrap())
+            .map(BiddingContract)
+    }
+
     pub fn addr(&self) -> &Addr {
         &self.0
     }
 
     #[track_caller]
     pub fn bid(&self, app: &mut App, sender: &Addr, funds: &[Coin]) -> Result<(), ContractError> {
-        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Bid {}, funds)
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Bid { referrer: None }, funds)
             .map_err(|err| err.downcast::<ContractError>().unwrap())?;
 
         Ok(())
     }
 
+    #[track_caller]
+    pub fn bid_referred(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        funds: &[Coin],
+        referrer: &Addr,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Bid {
+                referrer: Some(referrer.to_string()),
+            },
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
     #[track_caller]
     pub fn close(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
         app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Close {}, &[])
@@ -66,6 +113,388 @@ impl BiddingContract {
         Ok(())
     }
 
+    #[track_caller]
+    pub fn propose_owner(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        new_owner: String,
+        expiry: Option<Expiration>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::ProposeOwner { new_owner, expiry },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn accept_ownership(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::AcceptOwnership {},
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn renounce_ownership(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::RenounceOwnership {},
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_allowlist(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::UpdateAllowlist { add, remove },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_denylist(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::UpdateDenylist { add, remove },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn register(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        funds: &[Coin],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Register {}, funds)
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn confirm_purchase(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::ConfirmPurchase {},
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn slash_bond(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::SlashBond {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn advance_settlement(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::AdvanceSettlement {},
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn confirm_delivery(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::ConfirmDelivery {},
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn dispute(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Dispute {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn resolve_dispute(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        buyer_amount: u128,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::ResolveDispute {
+                buyer_amount: Uint128::new(buyer_amount),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn claim_escrow(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::ClaimEscrow {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn pause(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Pause {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn unpause(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Unpause {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn cancel(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Cancel {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn grant_role(&self, app: &mut App, sender: &Addr, role: Role, address: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::GrantRole {
+                role,
+                address: address.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn revoke_role(&self, app: &mut App, sender: &Addr, role: Role, address: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::RevokeRole {
+                role,
+                address: address.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_commission(&self, app: &mut App, sender: &Addr, commission: Decimal) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::UpdateCommission { commission }, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn sudo(&self, app: &mut App, msg: &SudoMsg) -> Result<(), ContractError> {
+        app.wasm_sudo(self.0.clone(), msg)
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn add_hook(&self, app: &mut App, sender: &Addr, addr: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::AddHook {
+                addr: addr.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn remove_hook(&self, app: &mut App, sender: &Addr, addr: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::RemoveHook {
+                addr: addr.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn deposit(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        pubkey: &[u8],
+        funds: &[Coin],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Deposit {
+                pubkey: Binary::from(pubkey),
+            },
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_deposit(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::WithdrawDeposit {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn relay_bid(
+        &self,
+        app: &mut App,
+        relayer: &Addr,
+        bid: &BidIntent,
+        signature: &[u8],
+        pubkey: &[u8],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            relayer.clone(),
+            self.0.clone(),
+            &ExecMsg::RelayBid {
+                bid: bid.clone(),
+                signature: Binary::from(signature),
+                pubkey: Binary::from(pubkey),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn claim_credit(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::ClaimCredit {}, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_fees(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        amount: Option<u128>,
+        recipient: Option<&Addr>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::WithdrawFees {
+                amount: amount.map(Uint128::new),
+                recipient: recipient.map(Addr::to_string),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_fee_split(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        fee_split: &[(&Addr, Decimal)],
+    ) -> Result<(), ContractError> {
+        let fee_split = fee_split
+            .iter()
+            .map(|(beneficiary, weight)| (beneficiary.to_string(), *weight))
+            .collect();
+
+        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::UpdateFeeSplit { fee_split }, &[])
+            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
     #[track_caller]
     pub fn query_highest_bid(&self, app: &App) -> StdResult<HighestBidResp> {
         app.wrap()
@@ -75,7 +504,7 @@ impl BiddingContract {
     #[track_caller]
     pub fn query_address_bid(&self, app: &App, address: String) -> StdResult<AddressBidResp> {
         app.wrap()
-            .query_wasm_smart(self.0.clone(), &QueryMsg::AddressBid { address: address })
+            .query_wasm_smart(self.0.clone(), &QueryMsg::AddressBid { address })
     }
 
     #[track_caller]
@@ -83,4 +512,156 @@ impl BiddingContract {
         app.wrap()
             .query_wasm_smart(self.0.clone(), &QueryMsg::Winner {})
     }
+
+    #[track_caller]
+    pub fn query_all_bids(
+        &self,
+        app: &App,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllBidsResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::AllBids { start_after, limit })
+    }
+
+    #[track_caller]
+    pub fn query_top_bids(&self, app: &App, limit: Option<u32>) -> StdResult<TopBidsResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::TopBids { limit })
+    }
+
+    #[track_caller]
+    pub fn query_bid_history(
+        &self,
+        app: &App,
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<BidHistoryResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::BidHistory {
+                address,
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_status(&self, app: &App) -> StdResult<StatusResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Status {})
+    }
+
+    #[track_caller]
+    pub fn query_config(&self, app: &App) -> StdResult<ConfigResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Config {})
+    }
+
+    #[track_caller]
+    pub fn query_ownership(&self, app: &App) -> StdResult<OwnershipResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Ownership {})
+    }
+
+    #[track_caller]
+    pub fn query_roles(&self, app: &App) -> StdResult<RolesResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Roles {})
+    }
+
+    #[track_caller]
+    pub fn query_credit(&self, app: &App, address: &Addr) -> StdResult<CreditResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Credit {
+                address: address.to_string(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_deposit(&self, app: &App, address: &Addr) -> StdResult<DepositResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Deposit {
+                address: address.to_string(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_fees(&self, app: &App) -> StdResult<FeesResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Fees {})
+    }
+
+    #[track_caller]
+    pub fn query_referrals(&self, app: &App, referrer: &Addr) -> StdResult<ReferralsResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Referrals {
+                referrer: referrer.to_string(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_simulate_bid(&self, app: &App, address: &Addr, funds: &[Coin]) -> StdResult<SimulateBidResp> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::SimulateBid {
+                address: address.to_string(),
+                funds: funds.to_vec(),
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn query_hooks(&self, app: &App) -> StdResult<HooksResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Hooks {})
+    }
+
+    #[track_caller]
+    pub fn query_allowlist(
+        &self,
+        app: &App,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AddressListResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Allowlist { start_after, limit })
+    }
+
+    #[track_caller]
+    pub fn query_denylist(
+        &self,
+        app: &App,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AddressListResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Denylist { start_after, limit })
+    }
+
+    #[track_caller]
+    pub fn query_bond(&self, app: &App, address: String) -> StdResult<BondResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Bond { address })
+    }
+
+    #[track_caller]
+    pub fn query_settlement(&self, app: &App) -> StdResult<SettlementResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Settlement {})
+    }
+
+    #[track_caller]
+    pub fn query_escrow(&self, app: &App) -> StdResult<EscrowResp> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Escrow {})
+    }
 }
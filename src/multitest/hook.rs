@@ -0,0 +1,119 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+    WasmMsg,
+};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use crate::msg::{BiddingHookExecuteMsg, BiddingHookMsg, ExecMsg};
+
+const FAIL: Item<bool> = Item::new("fail");
+const RETRACT_ON_CLOSE: Item<bool> = Item::new("retract_on_close");
+const RECEIVED: Item<Vec<BiddingHookMsg>> = Item::new("received");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct InstantiateMsg {
+    pub fail: bool,
+    /// Retracts the hook's own bid when notified of the close
+    pub retract_on_close: bool,
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    FAIL.save(deps.storage, &msg.fail)?;
+    RETRACT_ON_CLOSE.save(deps.storage, &msg.retract_on_close)?;
+    RECEIVED.save(deps.storage, &vec![])?;
+
+    Ok(Response::new())
+}
+
+fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: BiddingHookExecuteMsg,
+) -> StdResult<Response> {
+    if FAIL.load(deps.storage)? {
+        return Err(StdError::generic_err("The mock hook rejects every message"));
+    }
+
+    let BiddingHookExecuteMsg::BiddingHook(msg) = msg;
+    let mut resp = Response::new();
+    if matches!(msg, BiddingHookMsg::Closed { .. }) && RETRACT_ON_CLOSE.load(deps.storage)? {
+        resp = resp.add_message(WasmMsg::Execute {
+            contract_addr: info.sender.into_string(),
+            msg: to_binary(&ExecMsg::Retract { receiver: None })?,
+            funds: vec![],
+        });
+    }
+
+    RECEIVED.update(deps.storage, |mut received| -> StdResult<_> {
+        received.push(msg);
+        Ok(received)
+    })?;
+
+    Ok(resp)
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_binary(&RECEIVED.load(deps.storage)?)
+}
+
+/// Hook contract recording every `BiddingHookMsg`, or rejecting them all when `fail` is set
+#[derive(Debug)]
+pub struct MockHook(Addr);
+
+impl MockHook {
+    pub fn store_code(app: &mut App) -> u64 {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+        app.store_code(Box::new(contract))
+    }
+
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App,
+        code_id: u64,
+        sender: &Addr,
+        label: &str,
+        fail: bool,
+    ) -> StdResult<MockHook> {
+        Self::instantiate_with_msg(
+            app,
+            code_id,
+            sender,
+            label,
+            &InstantiateMsg {
+                fail,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[track_caller]
+    pub fn instantiate_with_msg(
+        app: &mut App,
+        code_id: u64,
+        sender: &Addr,
+        label: &str,
+        msg: &InstantiateMsg,
+    ) -> StdResult<MockHook> {
+        app.instantiate_contract(code_id, sender.clone(), msg, &[], label, None)
+            .map_err(|err| err.downcast().unwrap())
+            .map(MockHook)
+    }
+
+    #[track_caller]
+    pub fn query_received(&self, app: &App) -> StdResult<Vec<BiddingHookMsg>> {
+        app.wrap().query_wasm_smart(self.0.clone(), &Empty {})
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+}
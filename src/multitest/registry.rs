@@ -0,0 +1,78 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_storage_plus::Map;
+
+use crate::msg::{IsEligibleResp, RegistryQueryMsg};
+
+const ELIGIBLE: Map<&Addr, Empty> = Map::new("eligible");
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub eligible: Vec<String>,
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    for address in msg.eligible {
+        let address = deps.api.addr_validate(&address)?;
+        ELIGIBLE.save(deps.storage, &address, &Empty {})?;
+    }
+
+    Ok(Response::new())
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Err(StdError::generic_err(
+        "The mock registry has no execute messages",
+    ))
+}
+
+fn query(deps: Deps, _env: Env, msg: RegistryQueryMsg) -> StdResult<Binary> {
+    match msg {
+        RegistryQueryMsg::IsEligible { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&IsEligibleResp {
+                eligible: ELIGIBLE.has(deps.storage, &address),
+            })
+        }
+    }
+}
+
+/// Eligibility registry answering `IsEligible` from a fixed list of addresses
+#[derive(Debug)]
+pub struct MockRegistry(Addr);
+
+impl MockRegistry {
+    pub fn store_code(app: &mut App) -> u64 {
+        let contract = ContractWrapper::new(execute, instantiate, query);
+        app.store_code(Box::new(contract))
+    }
+
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App,
+        code_id: u64,
+        sender: &Addr,
+        label: &str,
+        eligible: &[&Addr],
+    ) -> StdResult<MockRegistry> {
+        let msg = InstantiateMsg {
+            eligible: eligible.iter().map(|address| address.to_string()).collect(),
+        };
+
+        app.instantiate_contract(code_id, sender.clone(), &msg, &[], label, None)
+            .map_err(|err| err.downcast().unwrap())
+            .map(MockRegistry)
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+}
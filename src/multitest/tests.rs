@@ -1,14 +1,23 @@
-use cosmwasm_std::{Addr, Coin, Empty, coins};
-use cw_multi_test::{App, Contract, ContractWrapper};
+use cosmwasm_std::{Addr, Coin, Decimal, Empty, Uint128, coins, to_vec};
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_utils::Expiration;
 
 use crate::{
     execute, instantiate,
-    multitest::BiddingContract,
-    query, error::ContractError,
+    multitest::{hook::{self, MockHook}, registry::MockRegistry, BiddingContract},
+    query, reply, sudo, error::ContractError,
+    events::{BidEvent, CloseEvent, RetractEvent},
+    msg::{
+        AuctionStatus, BidIntent, BiddingHookMsg, BidHistoryEntry, BidInfo, BondConfig, CommissionSchedule, ConfigResp, EscrowConfig,
+        EscrowInfo, ExecMsg, FeesResp, InstantiateMsg, OwnershipResp, ReferralsResp, Role, RolesResp, SimulateBidResp, SudoMsg, TimeTier, VolumeTier,
+    },
 };
 
 fn bidding_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(execute, instantiate, query);
+    let contract = ContractWrapper::new(execute, instantiate, query)
+        .with_sudo(sudo)
+        .with_reply(reply);
     Box::new(contract)
 }
 
@@ -56,7 +65,7 @@ fn query_address_bid_no_bids() {
 
     let resp = contract.query_address_bid(&app, owner.to_string()).unwrap();
 
-    assert_eq!(resp.bid, Coin::new(0, ATOM));
+    assert_eq!(resp.bid, None);
 }
 
 #[test]
@@ -78,8 +87,35 @@ fn query_winner_no_bids() {
 
     let resp = contract.query_winner(&app).unwrap();
 
-    assert_eq!(resp.address, "");
-    assert_eq!(resp.bid, Coin::new(0, ATOM));
+    assert_eq!(resp.winner, None);
+}
+
+#[test]
+fn close_without_bids() {
+    let mut app = App::default();
+    let creator = Addr::unchecked("creator");
+    let owner = Addr::unchecked("owner");
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &creator,
+        None,
+        "Bidding Contract",
+        Some(owner.clone()),
+    )
+    .unwrap();
+
+    contract.close(&mut app, &owner).unwrap();
+
+    let resp = contract.query_winner(&app).unwrap();
+    assert_eq!(resp.winner, None);
+    assert_eq!(contract.query_status(&app).unwrap().status, AuctionStatus::Closed);
+
+    let err = contract.close(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed {});
 }
 
 #[test]
@@ -306,9 +342,10 @@ fn successul_scenario() {
     contract.bid(&mut app, &sender3, &[Coin::new(1, ATOM)]).unwrap();
     contract.bid(&mut app, &sender1, &[Coin::new(5, ATOM)]).unwrap();
     contract.bid(&mut app, &sender2, &[Coin::new(10, ATOM)]).unwrap();
-    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(1, ATOM)]);
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(1, ATOM));
     contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
-    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(2, ATOM)]);
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(2, ATOM));
+    assert!(app.wrap().query_all_balances(owner.clone()).unwrap().is_empty());
 
     contract.close(&mut app, &owner).unwrap();
 
@@ -327,12 +364,18 @@ fn successul_scenario() {
     assert_eq!(highest_bid.bid, Coin::new(15, ATOM));
     assert_eq!(highest_bid.address, sender1);
 
-    let winner = contract.query_winner(&app).unwrap();
+    let winner = contract.query_winner(&app).unwrap().winner.unwrap();
     assert_eq!(winner.bid, Coin::new(15, ATOM));
     assert_eq!(winner.address, sender1);
 
+    let resp = contract.query_address_bid(&app, sender2.to_string()).unwrap();
+    assert_eq!(resp.bid, Some(Coin::new(10, ATOM)));
+
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(14, ATOM)]);
+
+    contract.withdraw_fees(&mut app, &owner, None, None).unwrap();
     assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(16, ATOM)]);
-    
+
     let err = contract.retract(&mut app, &sender1, None).unwrap_err();
     assert_eq!(
         err,
@@ -434,3 +477,2607 @@ fn custom_owner() {
 
     contract.close(&mut app, &custom_owner).unwrap();
 }
+
+#[test]
+fn query_all_bids() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let sender3 = Addr::unchecked("sender3");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(20, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender3, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    let resp = contract.query_all_bids(&app, None, None).unwrap();
+    assert_eq!(resp.bids, vec![]);
+    assert_eq!(resp.total_count, 0);
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(15, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender3, &[Coin::new(20, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let resp = contract.query_all_bids(&app, None, Some(2)).unwrap();
+    assert_eq!(
+        resp.bids,
+        vec![
+            BidInfo {
+                address: sender1.clone(),
+                bid: Coin::new(10, ATOM),
+                net_bid: Coin::new(9, ATOM),
+                commission: Coin::new(1, ATOM),
+            },
+            BidInfo {
+                address: sender2.clone(),
+                bid: Coin::new(15, ATOM),
+                net_bid: Coin::new(14, ATOM),
+                commission: Coin::new(1, ATOM),
+            },
+        ]
+    );
+    assert_eq!(resp.total_count, 3);
+
+    let resp = contract
+        .query_all_bids(&app, Some(sender2.to_string()), None)
+        .unwrap();
+    assert_eq!(
+        resp.bids,
+        vec![BidInfo {
+            address: sender3.clone(),
+            bid: Coin::new(20, ATOM),
+            net_bid: Coin::new(18, ATOM),
+            commission: Coin::new(2, ATOM),
+        }]
+    );
+    assert_eq!(resp.total_count, 3);
+
+    contract.retract(&mut app, &sender1, None).unwrap();
+    let resp = contract.query_all_bids(&app, None, None).unwrap();
+    assert_eq!(resp.bids.len(), 2);
+    assert_eq!(resp.total_count, 2);
+}
+
+#[test]
+fn query_top_bids() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let sender3 = Addr::unchecked("sender3");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(30, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender3, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(15, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender3, &[Coin::new(20, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender1, &[Coin::new(20, ATOM)]).unwrap();
+
+    let resp = contract.query_top_bids(&app, Some(2)).unwrap();
+    let top: Vec<_> = resp.bids.into_iter().map(|bid| (bid.address, bid.bid)).collect();
+    assert_eq!(
+        top,
+        vec![
+            (sender1.clone(), Coin::new(30, ATOM)),
+            (sender3.clone(), Coin::new(20, ATOM)),
+        ]
+    );
+
+    contract.close(&mut app, &owner).unwrap();
+    contract.retract(&mut app, &sender3, None).unwrap();
+
+    let resp = contract.query_top_bids(&app, None).unwrap();
+    let top: Vec<_> = resp.bids.into_iter().map(|bid| (bid.address, bid.bid)).collect();
+    assert_eq!(
+        top,
+        vec![
+            (sender1.clone(), Coin::new(30, ATOM)),
+            (sender2.clone(), Coin::new(15, ATOM)),
+        ]
+    );
+
+    let err = contract.retract(&mut app, &sender3, None).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoBidFound { address: sender3.to_string() }
+    );
+}
+
+#[test]
+fn query_bid_history() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(30, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+    contract.bid(&mut app, &sender2, &[Coin::new(15, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender1, &[Coin::new(20, ATOM)]).unwrap();
+
+    let resp = contract
+        .query_bid_history(&app, sender1.to_string(), None, None)
+        .unwrap();
+    let block = app.block_info();
+    assert_eq!(
+        resp.bids,
+        vec![
+            BidHistoryEntry {
+                id: 0,
+                timestamp: block.time.minus_seconds(5),
+                height: block.height - 1,
+                amount: Coin::new(10, ATOM),
+                commission: Coin::new(1, ATOM),
+                total: Coin::new(10, ATOM),
+            },
+            BidHistoryEntry {
+                id: 1,
+                timestamp: block.time,
+                height: block.height,
+                amount: Coin::new(20, ATOM),
+                commission: Coin::new(2, ATOM),
+                total: Coin::new(30, ATOM),
+            },
+        ]
+    );
+
+    let resp = contract
+        .query_bid_history(&app, sender1.to_string(), Some(0), None)
+        .unwrap();
+    assert_eq!(resp.bids.len(), 1);
+    assert_eq!(resp.bids[0].id, 1);
+
+    let resp = contract
+        .query_bid_history(&app, sender2.to_string(), None, None)
+        .unwrap();
+    assert_eq!(resp.bids.len(), 1);
+    assert_eq!(resp.bids[0].total, Coin::new(15, ATOM));
+}
+
+#[test]
+fn query_status() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+    let now = app.block_info().time;
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            start_time: Some(now.plus_seconds(10)),
+            end_time: Some(now.plus_seconds(100)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::NotStarted);
+
+    let err = contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::BiddingNotStarted {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Open);
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(90));
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Closed);
+
+    let err = contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed {});
+}
+
+#[test]
+fn retract_after_end_time() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+    let now = app.block_info().time;
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            end_time: Some(now.plus_seconds(100)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+
+    let err = contract.retract(&mut app, &sender1, None).unwrap_err();
+    assert_eq!(err, ContractError::BiddingNotClosed {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    assert_eq!(contract.query_status(&app).unwrap().status, AuctionStatus::Closed);
+
+    // The bidding ended, losers don't have to wait for `close`
+    contract.retract(&mut app, &sender1, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender1).unwrap(), &[Coin::new(9, ATOM)]);
+
+    let err = contract.retract(&mut app, &sender2, None).unwrap_err();
+    assert_eq!(err, ContractError::WinnerCannotRetract {});
+
+    contract.close(&mut app, &owner).unwrap();
+    let resp = contract.query_winner(&app).unwrap();
+    assert_eq!(resp.winner.unwrap().address, sender2);
+}
+
+#[test]
+fn query_config() {
+    let mut app = App::default();
+    let owner = Addr::unchecked("owner");
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            denom: Some("uosmo".to_string()),
+            commission: Some(Decimal::percent(5)),
+            description: Some("Vintage bicycle".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(
+        resp,
+        ConfigResp {
+            owner: Some(owner.clone()),
+            denom: "uosmo".to_string(),
+            commission: Decimal::percent(5),
+            start_time: None,
+            end_time: None,
+            description: Some("Vintage bicycle".to_string()),
+            allowlist_enabled: false,
+            eligibility_registry: None,
+            bond: None,
+            provisional_window: None,
+            escrow: None,
+            pause_retract: false,
+            fee_split: vec![],
+            referral_share: Decimal::zero(),
+            commission_schedule: None,
+            refund_commissions: false,
+        }
+    );
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Open);
+
+    contract.close(&mut app, &owner).unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Closed);
+}
+
+#[test]
+fn ownership_transfer() {
+    let mut app = App::default();
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("new_owner");
+    let sender = Addr::unchecked("sender");
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    let err = contract
+        .propose_owner(&mut app, &sender, new_owner.to_string(), None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized { owner: owner.to_string() }
+    );
+
+    let err = contract.accept_ownership(&mut app, &new_owner).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingOwner {});
+
+    let expiry = Expiration::AtHeight(app.block_info().height + 10);
+    contract
+        .propose_owner(&mut app, &owner, new_owner.to_string(), Some(expiry))
+        .unwrap();
+
+    let resp = contract.query_ownership(&app).unwrap();
+    assert_eq!(
+        resp,
+        OwnershipResp {
+            owner: Some(owner.clone()),
+            pending_owner: Some(new_owner.clone()),
+            pending_expiry: Some(expiry),
+        }
+    );
+
+    let err = contract.accept_ownership(&mut app, &sender).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotPendingOwner { pending_owner: new_owner.to_string() }
+    );
+
+    contract.accept_ownership(&mut app, &new_owner).unwrap();
+
+    let resp = contract.query_ownership(&app).unwrap();
+    assert_eq!(
+        resp,
+        OwnershipResp {
+            owner: Some(new_owner.clone()),
+            pending_owner: None,
+            pending_expiry: None,
+        }
+    );
+
+    let err = contract.close(&mut app, &owner).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized { owner: new_owner.to_string() }
+    );
+
+    contract.close(&mut app, &new_owner).unwrap();
+}
+
+#[test]
+fn expired_ownership_proposal() {
+    let mut app = App::default();
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("new_owner");
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    let expiry = Expiration::AtHeight(app.block_info().height + 10);
+    contract
+        .propose_owner(&mut app, &owner, new_owner.to_string(), Some(expiry))
+        .unwrap();
+
+    app.update_block(|block| block.height += 10);
+
+    let err = contract.accept_ownership(&mut app, &new_owner).unwrap_err();
+    assert_eq!(err, ContractError::OwnershipProposalExpired {});
+}
+
+#[test]
+fn bidder_cannot_become_owner() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+
+    let err = contract
+        .propose_owner(&mut app, &owner, sender1.to_string(), None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidderCannotOwn {});
+
+    // Bidding after being proposed doesn't let the pending owner take over either
+    contract
+        .propose_owner(&mut app, &owner, sender2.to_string(), None)
+        .unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+
+    let err = contract.accept_ownership(&mut app, &sender2).unwrap_err();
+    assert_eq!(err, ContractError::BidderCannotOwn {});
+
+    let err = contract
+        .sudo(&mut app, &SudoMsg::UpdateOwner { owner: sender1.to_string() })
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidderCannotOwn {});
+}
+
+#[test]
+fn renounce_ownership() {
+    let mut app = App::default();
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let owner = Addr::unchecked("owner");
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    let err = contract.renounce_ownership(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::BiddingNotClosed {});
+
+    contract.close(&mut app, &owner).unwrap();
+    contract.renounce_ownership(&mut app, &owner).unwrap();
+
+    let resp = contract.query_ownership(&app).unwrap();
+    assert_eq!(resp.owner, None);
+
+    let err = contract.renounce_ownership(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::NoOwner {});
+}
+
+#[test]
+fn invalid_instantiate_params() {
+    let mut app = App::default();
+    let owner = Addr::unchecked("owner");
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            owner: Some("Invalid Owner".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAddress { address: "Invalid Owner".to_string() }
+    );
+
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            commission: Some(Decimal::percent(101)),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCommission {});
+
+    let now = app.block_info().time;
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            start_time: Some(now.plus_seconds(10)),
+            end_time: Some(now.plus_seconds(10)),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTimeWindow {});
+}
+
+#[test]
+fn retract_to_invalid_receiver() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let err = contract
+        .retract(&mut app, &sender1, Some("Invalid Receiver".to_string()))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAddress { address: "Invalid Receiver".to_string() }
+    );
+
+    contract.retract(&mut app, &sender1, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender1.clone()).unwrap(), &[Coin::new(9, ATOM)]);
+}
+
+#[test]
+fn allowlist_and_denylist() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let sender3 = Addr::unchecked("sender3");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender3, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            allowlist: Some(vec![sender1.to_string(), sender2.to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = contract.bid(&mut app, &sender3, &[Coin::new(30, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::NotPermitted { address: sender3.to_string() });
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+
+    let err = contract
+        .update_denylist(&mut app, &sender1, vec![sender2.to_string()], vec![])
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract
+        .update_denylist(&mut app, &owner, vec![sender2.to_string()], vec![])
+        .unwrap();
+    contract
+        .update_allowlist(&mut app, &owner, vec![sender3.to_string()], vec![sender1.to_string()])
+        .unwrap();
+
+    let err = contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::NotPermitted { address: sender2.to_string() });
+
+    contract.bid(&mut app, &sender3, &[Coin::new(30, ATOM)]).unwrap();
+
+    let resp = contract.query_allowlist(&app, None, None).unwrap();
+    assert_eq!(resp.addresses, vec![sender2.clone(), sender3.clone()]);
+
+    let resp = contract.query_allowlist(&app, Some(sender2.to_string()), None).unwrap();
+    assert_eq!(resp.addresses, vec![sender3.clone()]);
+
+    let resp = contract.query_denylist(&app, None, None).unwrap();
+    assert_eq!(resp.addresses, vec![sender2.clone()]);
+}
+
+#[test]
+fn eligibility_registry() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let registry_id = MockRegistry::store_code(&mut app);
+    let registry =
+        MockRegistry::instantiate(&mut app, registry_id, &owner, "Registry", &[&sender1]).unwrap();
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            eligibility_registry: Some(registry.addr().to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.eligibility_registry, Some(registry.addr().clone()));
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+
+    let err = contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::NotEligible { address: sender2.to_string() });
+
+    let resp = contract.query_highest_bid(&app).unwrap();
+    assert_eq!(resp.address, sender1);
+}
+
+fn bonded_bidding(app: &mut App, owner: &Addr) -> BiddingContract {
+    let contract_id = app.store_code(bidding_contract());
+
+    BiddingContract::instantiate_with_msg(
+        app,
+        contract_id,
+        owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            bond: Some(BondConfig {
+                amount: Uint128::new(5),
+                settlement_period: 100,
+            }),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn zero_bond_rejected() {
+    let mut app = App::default();
+    let owner = Addr::unchecked("owner");
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            bond: Some(BondConfig {
+                amount: Uint128::zero(),
+                settlement_period: 100,
+            }),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidBond {});
+}
+
+#[test]
+fn bond_returned_on_settlement() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(15, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(25, ATOM))
+            .unwrap();
+    });
+
+    let contract = bonded_bidding(&mut app, &owner);
+
+    let err = contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::NotRegistered {});
+
+    let err = contract.register(&mut app, &sender1, &[Coin::new(4, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::IncorrectBond { bond: Coin::new(5, ATOM).to_string() });
+
+    contract.register(&mut app, &sender1, &[Coin::new(5, ATOM)]).unwrap();
+    contract.register(&mut app, &sender2, &[Coin::new(5, ATOM)]).unwrap();
+
+    let err = contract.register(&mut app, &sender1, &[Coin::new(5, ATOM)]).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyRegistered {});
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let resp = contract.query_bond(&app, sender2.to_string()).unwrap();
+    assert_eq!(resp.bond, Some(Coin::new(5, ATOM)));
+
+    let err = contract.confirm_purchase(&mut app, &sender1).unwrap_err();
+    assert_eq!(err, ContractError::NotWinner {});
+
+    contract.retract(&mut app, &sender1, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender1.clone()).unwrap(), &[Coin::new(14, ATOM)]);
+
+    contract.confirm_purchase(&mut app, &sender2).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender2.clone()).unwrap(), &[Coin::new(5, ATOM)]);
+
+    let resp = contract.query_bond(&app, sender2.to_string()).unwrap();
+    assert_eq!(resp.bond, None);
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Settled);
+}
+
+#[test]
+fn bond_slashed_after_settlement_period() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(15, ATOM))
+            .unwrap();
+    });
+
+    let contract = bonded_bidding(&mut app, &owner);
+
+    contract.register(&mut app, &sender, &[Coin::new(5, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let resp = contract.query_settlement(&app).unwrap().settlement.unwrap();
+    assert_eq!(resp.deadline, app.block_info().time.plus_seconds(100));
+
+    let err = contract.slash_bond(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::SettlementNotExpired {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract.confirm_purchase(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::SettlementExpired {});
+
+    contract.slash_bond(&mut app, &sender).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(14, ATOM)]);
+
+    let resp = contract.query_settlement(&app).unwrap().settlement.unwrap();
+    assert!(resp.slashed);
+
+    let err = contract.confirm_purchase(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::NoSettlementPending {});
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Closed);
+}
+
+fn provisional_bidding(app: &mut App, owner: &Addr) -> BiddingContract {
+    let contract_id = app.store_code(bidding_contract());
+
+    BiddingContract::instantiate_with_msg(
+        app,
+        contract_id,
+        owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            bond: Some(BondConfig {
+                amount: Uint128::new(5),
+                settlement_period: 100,
+            }),
+            provisional_window: Some(50),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn provisional_winner_falls_back_to_runner_up() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let keeper = Addr::unchecked("keeper");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(25, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(35, ATOM))
+            .unwrap();
+    });
+
+    let contract = provisional_bidding(&mut app, &owner);
+
+    contract.register(&mut app, &sender1, &[Coin::new(5, ATOM)]).unwrap();
+    contract.register(&mut app, &sender2, &[Coin::new(5, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender1, &[Coin::new(20, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(30, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    // Nothing is paid out until the winner confirms, the commissions are collected as fees
+    assert!(app.wrap().query_all_balances(owner.clone()).unwrap().is_empty());
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(5, ATOM));
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::AwaitingConfirmation);
+
+    let err = contract.advance_settlement(&mut app, &keeper).unwrap_err();
+    assert_eq!(err, ContractError::SettlementNotExpired {});
+
+    let err = contract.slash_bond(&mut app, &keeper).unwrap_err();
+    assert_eq!(err, ContractError::ProvisionalSettlement {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(50));
+    contract.advance_settlement(&mut app, &keeper).unwrap();
+
+    // sender2's bond is forfeited and the lot is offered to sender1
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(5, ATOM)]);
+
+    let winner = contract.query_winner(&app).unwrap().winner.unwrap();
+    assert_eq!(winner.address, sender1);
+
+    let resp = contract.query_settlement(&app).unwrap().settlement.unwrap();
+    assert_eq!(resp.deadline, app.block_info().time.plus_seconds(50));
+
+    contract.retract(&mut app, &sender2, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender2.clone()).unwrap(), &[Coin::new(27, ATOM)]);
+
+    let err = contract.retract(&mut app, &sender1, None).unwrap_err();
+    assert_eq!(err, ContractError::WinnerCannotRetract {});
+
+    contract.confirm_purchase(&mut app, &sender1).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(23, ATOM)]);
+    assert_eq!(app.wrap().query_all_balances(sender1.clone()).unwrap(), &[Coin::new(5, ATOM)]);
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Settled);
+}
+
+#[test]
+fn provisional_winners_exhausted() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(15, ATOM))
+            .unwrap();
+    });
+
+    let contract = provisional_bidding(&mut app, &owner);
+
+    contract.register(&mut app, &sender, &[Coin::new(5, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(50));
+    contract.advance_settlement(&mut app, &owner).unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Unsold);
+
+    let resp = contract.query_winner(&app).unwrap();
+    assert_eq!(resp.winner, None);
+
+    let err = contract.advance_settlement(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::NoSettlementPending {});
+
+    contract.retract(&mut app, &sender, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender.clone()).unwrap(), &[Coin::new(9, ATOM)]);
+}
+
+fn escrowed_bidding(app: &mut App, owner: &Addr, arbiter: &Addr) -> BiddingContract {
+    let contract_id = app.store_code(bidding_contract());
+
+    BiddingContract::instantiate_with_msg(
+        app,
+        contract_id,
+        owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            escrow: Some(EscrowConfig {
+                arbiter: arbiter.to_string(),
+                timeout: 100,
+            }),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn escrow_released_on_delivery() {
+    let owner = Addr::unchecked("owner");
+    let arbiter = Addr::unchecked("arbiter");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract = escrowed_bidding(&mut app, &owner, &arbiter);
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+    assert!(app.wrap().query_all_balances(owner.clone()).unwrap().is_empty());
+
+    let resp = contract.query_escrow(&app).unwrap();
+    assert_eq!(
+        resp.escrow,
+        Some(EscrowInfo {
+            buyer: sender.clone(),
+            amount: Coin::new(9, ATOM),
+            deadline: app.block_info().time.plus_seconds(100),
+            disputed: false,
+            released: false,
+        })
+    );
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::InEscrow);
+
+    let err = contract.claim_escrow(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::EscrowNotExpired {});
+
+    let err = contract.confirm_delivery(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::NotWinner {});
+
+    contract.confirm_delivery(&mut app, &sender).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(9, ATOM)]);
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Settled);
+
+    let err = contract.confirm_delivery(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::NoEscrow {});
+}
+
+#[test]
+fn escrow_claimed_after_timeout() {
+    let owner = Addr::unchecked("owner");
+    let arbiter = Addr::unchecked("arbiter");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract = escrowed_bidding(&mut app, &owner, &arbiter);
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract.dispute(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::EscrowExpired {});
+
+    contract.claim_escrow(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(9, ATOM)]);
+}
+
+#[test]
+fn escrow_dispute_resolved_by_arbiter() {
+    let owner = Addr::unchecked("owner");
+    let arbiter = Addr::unchecked("arbiter");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract = escrowed_bidding(&mut app, &owner, &arbiter);
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let err = contract.resolve_dispute(&mut app, &arbiter, 4).unwrap_err();
+    assert_eq!(err, ContractError::EscrowNotDisputed {});
+
+    contract.dispute(&mut app, &sender).unwrap();
+
+    let err = contract.confirm_delivery(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::EscrowDisputed {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract.claim_escrow(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::EscrowDisputed {});
+
+    let err = contract.resolve_dispute(&mut app, &owner, 4).unwrap_err();
+    assert_eq!(err, ContractError::NotArbiter { arbiter: arbiter.to_string() });
+
+    let err = contract.resolve_dispute(&mut app, &arbiter, 10).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSplit { buyer_amount: "10".to_string(), amount: "9".to_string() }
+    );
+
+    contract.resolve_dispute(&mut app, &arbiter, 4).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender.clone()).unwrap(), &[Coin::new(4, ATOM)]);
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(5, ATOM)]);
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Settled);
+}
+
+#[test]
+fn guardian_pauses_bidding() {
+    let owner = Addr::unchecked("owner");
+    let guardian = Addr::unchecked("guardian");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            guardian: Some(guardian.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+
+    let err = contract.pause(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract.pause(&mut app, &guardian).unwrap();
+    assert!(contract.query_status(&app).unwrap().paused);
+
+    let err = contract
+        .bid(&mut app, &sender, &[Coin::new(10, ATOM)])
+        .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    let err = contract.close(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    contract.unpause(&mut app, &owner).unwrap();
+    assert!(!contract.query_status(&app).unwrap().paused);
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+}
+
+#[test]
+fn pause_retract_blocks_withdrawals() {
+    let owner = Addr::unchecked("owner");
+    let guardian = Addr::unchecked("guardian");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            guardian: Some(guardian.to_string()),
+            pause_retract: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    contract.pause(&mut app, &guardian).unwrap();
+
+    let err = contract.retract(&mut app, &sender1, None).unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    contract.unpause(&mut app, &owner).unwrap();
+    contract.retract(&mut app, &sender1, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender1).unwrap(), &[Coin::new(9, ATOM)]);
+}
+
+#[test]
+fn operator_closes_without_owner_key() {
+    let owner = Addr::unchecked("owner");
+    let operator = Addr::unchecked("operator");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    let err = contract
+        .grant_role(&mut app, &operator, Role::Operator, &operator)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract
+        .grant_role(&mut app, &owner, Role::Operator, &operator)
+        .unwrap();
+
+    let resp = contract.query_roles(&app).unwrap();
+    assert_eq!(
+        resp,
+        RolesResp {
+            owner: Some(owner.clone()),
+            operators: vec![operator.clone()],
+            fee_admins: vec![],
+            guardians: vec![],
+        }
+    );
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+
+    contract.pause(&mut app, &operator).unwrap();
+    contract.unpause(&mut app, &operator).unwrap();
+
+    let err = contract.update_commission(&mut app, &operator, Decimal::percent(5)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract.close(&mut app, &operator).unwrap();
+
+    // Proceeds still go to the owner
+    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), &[Coin::new(9, ATOM)]);
+    assert!(app.wrap().query_all_balances(operator).unwrap().is_empty());
+}
+
+#[test]
+fn role_permissions() {
+    let owner = Addr::unchecked("owner");
+    let guardian = Addr::unchecked("guardian");
+    let fee_admin = Addr::unchecked("fee_admin");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            guardian: Some(guardian.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract
+        .grant_role(&mut app, &owner, Role::FeeAdmin, &fee_admin)
+        .unwrap();
+
+    let resp = contract.query_roles(&app).unwrap();
+    assert_eq!(resp.fee_admins, vec![fee_admin.clone()]);
+    assert_eq!(resp.guardians, vec![guardian.clone()]);
+
+    let err = contract
+        .update_commission(&mut app, &fee_admin, Decimal::percent(101))
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCommission {});
+
+    contract
+        .update_commission(&mut app, &fee_admin, Decimal::percent(20))
+        .unwrap();
+    assert_eq!(contract.query_config(&app).unwrap().commission, Decimal::percent(20));
+
+    let err = contract.close(&mut app, &fee_admin).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract.pause(&mut app, &guardian).unwrap();
+
+    let err = contract.unpause(&mut app, &guardian).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    let err = contract.cancel(&mut app, &guardian).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract.unpause(&mut app, &owner).unwrap();
+
+    contract
+        .revoke_role(&mut app, &owner, Role::Guardian, &guardian)
+        .unwrap();
+
+    let err = contract.pause(&mut app, &guardian).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(2, ATOM));
+}
+
+#[test]
+fn cancelled_auction_refunds_every_bidder() {
+    let owner = Addr::unchecked("owner");
+    let operator = Addr::unchecked("operator");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    contract
+        .grant_role(&mut app, &owner, Role::Operator, &operator)
+        .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+
+    let err = contract.retract(&mut app, &sender1, None).unwrap_err();
+    assert_eq!(err, ContractError::BiddingNotClosed {});
+
+    contract.cancel(&mut app, &operator).unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Cancelled);
+    assert_eq!(contract.query_winner(&app).unwrap().winner, None);
+
+    let err = contract.close(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed {});
+
+    let err = contract.cancel(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed {});
+
+    let err = contract.bid(&mut app, &sender1, &[]).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed {});
+
+    contract.retract(&mut app, &sender1, None).unwrap();
+    contract.retract(&mut app, &sender2, None).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(sender1).unwrap(), &[Coin::new(9, ATOM)]);
+    assert_eq!(app.wrap().query_all_balances(sender2).unwrap(), &[Coin::new(18, ATOM)]);
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(3, ATOM));
+}
+
+#[test]
+fn sudo_force_close() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.pause(&mut app, &owner).unwrap();
+
+    contract.sudo(&mut app, &SudoMsg::ForceClose {}).unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Closed);
+    assert_eq!(contract.query_winner(&app).unwrap().winner.unwrap().address, sender);
+    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), &[Coin::new(9, ATOM)]);
+
+    let err = contract.sudo(&mut app, &SudoMsg::ForceClose {}).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed {});
+}
+
+#[test]
+fn sudo_force_cancel() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+
+    contract.sudo(&mut app, &SudoMsg::ForceCancel {}).unwrap();
+
+    let resp = contract.query_status(&app).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Cancelled);
+
+    let err = contract.sudo(&mut app, &SudoMsg::ForceCancel {}).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed {});
+
+    contract.retract(&mut app, &sender, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), &[Coin::new(9, ATOM)]);
+}
+
+#[test]
+fn sudo_update_owner() {
+    let owner = Addr::unchecked("owner");
+    let pending = Addr::unchecked("pending");
+    let new_owner = Addr::unchecked("new_owner");
+
+    let mut app = App::default();
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    contract
+        .propose_owner(&mut app, &owner, pending.to_string(), None)
+        .unwrap();
+
+    let err = contract
+        .sudo(
+            &mut app,
+            &SudoMsg::UpdateOwner {
+                owner: String::new(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAddress {
+            address: String::new()
+        }
+    );
+
+    contract
+        .sudo(
+            &mut app,
+            &SudoMsg::UpdateOwner {
+                owner: new_owner.to_string(),
+            },
+        )
+        .unwrap();
+
+    let resp = contract.query_ownership(&app).unwrap();
+    assert_eq!(
+        resp,
+        OwnershipResp {
+            owner: Some(new_owner.clone()),
+            pending_owner: None,
+            pending_expiry: None,
+        }
+    );
+
+    let err = contract.accept_ownership(&mut app, &pending).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingOwner {});
+
+    let err = contract.close(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: new_owner.to_string() });
+
+    contract.close(&mut app, &new_owner).unwrap();
+}
+
+#[test]
+fn events_describe_bids_close_and_retract() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let receiver = Addr::unchecked("receiver");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    let resp = app
+        .execute_contract(
+            sender1.clone(),
+            contract.addr().clone(),
+            &ExecMsg::Bid { referrer: None },
+            &coins(10, ATOM),
+        )
+        .unwrap();
+    let event = resp.events.iter().find(|ev| ev.ty == "wasm-bid").unwrap();
+    assert_eq!(
+        BidEvent::parse(event).unwrap(),
+        BidEvent {
+            bidder: sender1.clone(),
+            amount: Coin::new(10, ATOM),
+            commission: Coin::new(1, ATOM),
+            total: Coin::new(10, ATOM),
+            previous_leader: None,
+            leader: sender1.clone(),
+        }
+    );
+
+    let resp = app
+        .execute_contract(
+            sender2.clone(),
+            contract.addr().clone(),
+            &ExecMsg::Bid { referrer: None },
+            &coins(20, ATOM),
+        )
+        .unwrap();
+    let event = resp.events.iter().find(|ev| ev.ty == "wasm-bid").unwrap();
+    let event = BidEvent::parse(event).unwrap();
+    assert_eq!(event.previous_leader, Some(sender1.clone()));
+    assert_eq!(event.leader, sender2);
+
+    let resp = app
+        .execute_contract(owner, contract.addr().clone(), &ExecMsg::Close {}, &[])
+        .unwrap();
+    let event = resp.events.iter().find(|ev| ev.ty == "wasm-close").unwrap();
+    assert_eq!(
+        CloseEvent::parse(event).unwrap(),
+        CloseEvent {
+            winner: Some(sender2),
+            winning_bid: Some(Coin::new(20, ATOM)),
+        }
+    );
+
+    let resp = app
+        .execute_contract(
+            sender1.clone(),
+            contract.addr().clone(),
+            &ExecMsg::Retract {
+                receiver: Some(receiver.to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+    let event = resp.events.iter().find(|ev| ev.ty == "wasm-retract").unwrap();
+    assert_eq!(
+        RetractEvent::parse(event).unwrap(),
+        RetractEvent {
+            bidder: sender1,
+            receiver,
+            amount: Coin::new(9, ATOM),
+        }
+    );
+
+    assert!(CloseEvent::parse(event).is_err());
+}
+
+#[test]
+fn hooks_notified_of_bids_and_close() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+    let hook_id = MockHook::store_code(&mut app);
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+    let hook = MockHook::instantiate(&mut app, hook_id, &owner, "Hook", false).unwrap();
+
+    let err = contract
+        .add_hook(&mut app, &sender1, hook.addr())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    contract.add_hook(&mut app, &owner, hook.addr()).unwrap();
+
+    let err = contract.add_hook(&mut app, &owner, hook.addr()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::HookAlreadyRegistered {
+            addr: hook.addr().to_string()
+        }
+    );
+    assert_eq!(contract.query_hooks(&app).unwrap().hooks, vec![hook.addr().clone()]);
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    assert_eq!(
+        hook.query_received(&app).unwrap(),
+        vec![
+            BiddingHookMsg::NewHighestBid {
+                bidder: sender1.clone(),
+                total: Coin::new(10, ATOM),
+            },
+            BiddingHookMsg::Outbid {
+                bidder: sender1,
+                total: Coin::new(10, ATOM),
+                outbid_by: sender2.clone(),
+            },
+            BiddingHookMsg::NewHighestBid {
+                bidder: sender2.clone(),
+                total: Coin::new(20, ATOM),
+            },
+            BiddingHookMsg::Closed {
+                winner: Some(sender2),
+                winning_bid: Some(Coin::new(20, ATOM)),
+            },
+        ]
+    );
+
+    contract.remove_hook(&mut app, &owner, hook.addr()).unwrap();
+    assert!(contract.query_hooks(&app).unwrap().hooks.is_empty());
+
+    let err = contract.remove_hook(&mut app, &owner, hook.addr()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::HookNotRegistered {
+            addr: hook.addr().to_string()
+        }
+    );
+}
+
+#[test]
+fn failing_hook_does_not_block_bidding() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+    let hook_id = MockHook::store_code(&mut app);
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+    let failing = MockHook::instantiate(&mut app, hook_id, &owner, "Failing hook", true).unwrap();
+    let hook = MockHook::instantiate(&mut app, hook_id, &owner, "Hook", false).unwrap();
+
+    contract.add_hook(&mut app, &owner, failing.addr()).unwrap();
+    contract.add_hook(&mut app, &owner, hook.addr()).unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let resp = contract.query_highest_bid(&app).unwrap();
+    assert_eq!(resp.bid, Coin::new(10, ATOM));
+    assert_eq!(hook.query_received(&app).unwrap().len(), 2);
+    assert!(failing.query_received(&app).unwrap().is_empty());
+}
+
+#[test]
+fn hook_retracting_on_close_does_not_block_proceeds() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+    let hook_id = MockHook::store_code(&mut app);
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+    let hook = MockHook::instantiate_with_msg(
+        &mut app,
+        hook_id,
+        &owner,
+        "Retracting hook",
+        &hook::InstantiateMsg {
+            retract_on_close: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.add_hook(&mut app, &owner, hook.addr()).unwrap();
+
+    // The hook bids too, and retracts its losing bid as soon as it learns about the close
+    app.send_tokens(sender.clone(), hook.addr().clone(), &coins(10, ATOM))
+        .unwrap();
+    contract.bid(&mut app, hook.addr(), &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender, &[Coin::new(20, ATOM)]).unwrap();
+
+    contract.close(&mut app, &owner).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), &[Coin::new(18, ATOM)]);
+    assert_eq!(app.wrap().query_all_balances(hook.addr()).unwrap(), &[Coin::new(9, ATOM)]);
+}
+
+#[test]
+fn failed_refund_credited_and_claimed() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let err = contract.claim_credit(&mut app, &sender1).unwrap_err();
+    assert_eq!(err, ContractError::NoCredit {});
+
+    // Drain the contract so the refund transfer fails
+    app.init_modules(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, contract.addr(), vec![])
+            .unwrap();
+    });
+
+    contract.retract(&mut app, &sender1, None).unwrap();
+    assert!(app.wrap().query_all_balances(sender1.clone()).unwrap().is_empty());
+    assert_eq!(
+        contract.query_credit(&app, &sender1).unwrap().credit,
+        Some(Coin::new(9, ATOM))
+    );
+
+    let err = contract.retract(&mut app, &sender1, None).unwrap_err();
+    assert_eq!(err, ContractError::NoBidFound { address: sender1.to_string() });
+
+    app.init_modules(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, contract.addr(), coins(9, ATOM))
+            .unwrap();
+    });
+
+    contract.claim_credit(&mut app, &sender1).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender1.clone()).unwrap(), &[Coin::new(9, ATOM)]);
+    assert_eq!(contract.query_credit(&app, &sender1).unwrap().credit, None);
+}
+
+#[test]
+fn fees_withdrawn_by_owner() {
+    let owner = Addr::unchecked("owner");
+    let treasury = Addr::unchecked("treasury");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let err = contract
+        .withdraw_fees(&mut app, &sender, None, None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { owner: owner.to_string() });
+
+    let err = contract
+        .withdraw_fees(&mut app, &owner, Some(3), None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFees { available: "2".to_string() });
+
+    let err = contract.renounce_ownership(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::FeesPending {});
+
+    contract
+        .withdraw_fees(&mut app, &owner, Some(1), Some(&treasury))
+        .unwrap();
+    assert_eq!(app.wrap().query_all_balances(treasury).unwrap(), &[Coin::new(1, ATOM)]);
+
+    let resp = contract.query_fees(&app).unwrap();
+    assert_eq!(
+        resp,
+        FeesResp {
+            accrued: Coin::new(2, ATOM),
+            withdrawn: Coin::new(1, ATOM),
+            available: Coin::new(1, ATOM),
+        }
+    );
+
+    contract.withdraw_fees(&mut app, &owner, None, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(19, ATOM)]);
+
+    contract.renounce_ownership(&mut app, &owner).unwrap();
+}
+
+#[test]
+fn fees_split_among_beneficiaries() {
+    let owner = Addr::unchecked("owner");
+    let platform = Addr::unchecked("platform");
+    let community = Addr::unchecked("community");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(70, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let split = |weights: [u64; 3]| {
+        Some(vec![
+            (owner.to_string(), Decimal::percent(weights[0])),
+            (platform.to_string(), Decimal::percent(weights[1])),
+            (community.to_string(), Decimal::percent(weights[2])),
+        ])
+    };
+
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            fee_split: split([70, 20, 5]),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFeeSplit {});
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            fee_split: split([70, 20, 10]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(70, ATOM)]).unwrap();
+    contract.close(&mut app, &owner).unwrap();
+
+    let err = contract
+        .withdraw_fees(&mut app, &owner, None, Some(&platform))
+        .unwrap_err();
+    assert_eq!(err, ContractError::FeeRecipientWithSplit {});
+
+    // 70% of 7 is rounded down to 4, 20% to 1 and 10% to 0, the remaining 2 go to the owner
+    contract.withdraw_fees(&mut app, &owner, None, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(69, ATOM)]);
+    assert_eq!(app.wrap().query_all_balances(platform.clone()).unwrap(), &[Coin::new(1, ATOM)]);
+    assert!(app.wrap().query_all_balances(community.clone()).unwrap().is_empty());
+
+    let err = contract
+        .update_fee_split(&mut app, &owner, &[(&platform, Decimal::percent(50)), (&community, Decimal::zero())])
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFeeSplit {});
+
+    // Fee admins adjust the commission, but cannot redirect the owner's fees
+    contract
+        .grant_role(&mut app, &owner, Role::FeeAdmin, &platform)
+        .unwrap();
+    let err = contract
+        .update_fee_split(&mut app, &platform, &[(&platform, Decimal::one())])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        }
+    );
+
+    contract
+        .update_fee_split(&mut app, &owner, &[(&platform, Decimal::one())])
+        .unwrap();
+    assert_eq!(
+        contract.query_config(&app).unwrap().fee_split,
+        vec![(platform, Decimal::one())]
+    );
+}
+
+#[test]
+fn referrer_rewarded_from_commissions() {
+    let owner = Addr::unchecked("owner");
+    let referrer = Addr::unchecked("referrer");
+    let other = Addr::unchecked("other");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(100, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            referral_share: Some(Decimal::percent(50)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = contract
+        .bid_referred(&mut app, &sender1, &[Coin::new(20, ATOM)], &sender1)
+        .unwrap_err();
+    assert_eq!(err, ContractError::SelfReferral {});
+
+    contract
+        .bid_referred(&mut app, &sender1, &[Coin::new(20, ATOM)], &referrer)
+        .unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(30, ATOM)]).unwrap();
+
+    // Only the first bid attributes the bidder
+    contract
+        .bid_referred(&mut app, &sender1, &[Coin::new(20, ATOM)], &other)
+        .unwrap();
+    contract
+        .bid_referred(&mut app, &sender2, &[Coin::new(20, ATOM)], &referrer)
+        .unwrap();
+
+    assert_eq!(
+        contract.query_referrals(&app, &referrer).unwrap(),
+        ReferralsResp {
+            bidders: 1,
+            rewards: Coin::new(2, ATOM),
+        }
+    );
+    assert_eq!(contract.query_referrals(&app, &other).unwrap().bidders, 0);
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(7, ATOM));
+
+    contract.claim_credit(&mut app, &referrer).unwrap();
+    assert_eq!(app.wrap().query_all_balances(referrer).unwrap(), &[Coin::new(2, ATOM)]);
+}
+
+#[test]
+fn volume_commission_tiers() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(300, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let schedule = |rates: [u64; 2]| CommissionSchedule::Volume {
+        tiers: vec![
+            VolumeTier {
+                from: Uint128::zero(),
+                rate: Decimal::percent(rates[0]),
+            },
+            VolumeTier {
+                from: Uint128::new(100),
+                rate: Decimal::percent(rates[1]),
+            },
+        ],
+    };
+
+    let err = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            commission_schedule: Some(schedule([5, 10])),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCommissionSchedule {});
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            commission_schedule: Some(schedule([10, 5])),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        contract
+            .query_simulate_bid(&app, &sender, &[Coin::new(80, ATOM)])
+            .unwrap(),
+        SimulateBidResp::Accepted {
+            commission: Coin::new(8, ATOM),
+            total: Coin::new(80, ATOM),
+        }
+    );
+    contract.bid(&mut app, &sender, &[Coin::new(80, ATOM)]).unwrap();
+
+    // 20 more at 10% and 100 above the first tier at 5%
+    assert_eq!(
+        contract
+            .query_simulate_bid(&app, &sender, &[Coin::new(120, ATOM)])
+            .unwrap(),
+        SimulateBidResp::Accepted {
+            commission: Coin::new(7, ATOM),
+            total: Coin::new(200, ATOM),
+        }
+    );
+    contract.bid(&mut app, &sender, &[Coin::new(120, ATOM)]).unwrap();
+
+    let resp = contract.query_address_bid(&app, sender.to_string()).unwrap();
+    assert_eq!(resp.bid, Some(Coin::new(200, ATOM)));
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(15, ATOM));
+
+    let err = contract
+        .update_commission(&mut app, &owner, Decimal::percent(20))
+        .unwrap_err();
+    assert_eq!(err, ContractError::CommissionScheduled {});
+}
+
+#[test]
+fn time_commission_tiers() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(300, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            commission_schedule: Some(CommissionSchedule::Time {
+                tiers: vec![
+                    TimeTier {
+                        after: 0,
+                        rate: Decimal::percent(10),
+                    },
+                    TimeTier {
+                        after: 3600,
+                        rate: Decimal::percent(2),
+                    },
+                ],
+            }),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(100, ATOM)]).unwrap();
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(10, ATOM));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(3600));
+
+    let resp = contract
+        .query_simulate_bid(&app, &sender, &[Coin::new(100, ATOM)])
+        .unwrap();
+    assert!(matches!(
+        resp,
+        SimulateBidResp::Accepted { commission, .. } if commission == Coin::new(2, ATOM)
+    ));
+
+    contract.bid(&mut app, &sender, &[Coin::new(100, ATOM)]).unwrap();
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(12, ATOM));
+}
+
+#[test]
+fn commissions_refunded_to_losers() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            refund_commissions: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+    contract.bid(&mut app, &sender2, &[Coin::new(20, ATOM)]).unwrap();
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(0, ATOM));
+
+    contract.close(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), &[Coin::new(18, ATOM)]);
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(2, ATOM));
+
+    contract.retract(&mut app, &sender1, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender1).unwrap(), &[Coin::new(10, ATOM)]);
+
+    contract.withdraw_fees(&mut app, &owner, None, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(owner).unwrap(), &[Coin::new(20, ATOM)]);
+    assert!(app.wrap().query_all_balances(contract.addr()).unwrap().is_empty());
+}
+
+#[test]
+fn cancelled_auction_refunds_commissions() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding Contract",
+        &InstantiateMsg {
+            refund_commissions: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender, &[Coin::new(10, ATOM)]).unwrap();
+    contract.cancel(&mut app, &owner).unwrap();
+
+    contract.retract(&mut app, &sender, None).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender).unwrap(), &[Coin::new(10, ATOM)]);
+    assert_eq!(contract.query_fees(&app).unwrap().accrued, Coin::new(0, ATOM));
+}
+
+#[test]
+fn simulate_bid() {
+    let owner = Addr::unchecked("owner");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    contract.bid(&mut app, &sender1, &[Coin::new(10, ATOM)]).unwrap();
+
+    assert_eq!(
+        contract
+            .query_simulate_bid(&app, &sender2, &[Coin::new(20, ATOM)])
+            .unwrap(),
+        SimulateBidResp::Accepted {
+            commission: Coin::new(2, ATOM),
+            total: Coin::new(20, ATOM),
+        }
+    );
+
+    let rejected = |err: ContractError| SimulateBidResp::Rejected {
+        error: err.to_string(),
+    };
+
+    assert_eq!(
+        contract
+            .query_simulate_bid(&app, &sender2, &[Coin::new(5, ATOM)])
+            .unwrap(),
+        rejected(ContractError::InsufficientBid {
+            bid: "5".to_owned(),
+            highest_bid: "10".to_owned(),
+        })
+    );
+    assert_eq!(
+        contract
+            .query_simulate_bid(&app, &sender2, &[Coin::new(20, "eth")])
+            .unwrap(),
+        rejected(ContractError::IncorrectBid {})
+    );
+    assert_eq!(
+        contract
+            .query_simulate_bid(&app, &owner, &[Coin::new(20, ATOM)])
+            .unwrap(),
+        rejected(ContractError::OwnerCannotBid {})
+    );
+
+    contract.close(&mut app, &owner).unwrap();
+
+    assert_eq!(
+        contract
+            .query_simulate_bid(&app, &sender2, &[Coin::new(20, ATOM)])
+            .unwrap(),
+        rejected(ContractError::BiddingAlreadyClosed {})
+    );
+}
+
+fn sign_bid(key: &SigningKey, bid: &BidIntent) -> Vec<u8> {
+    let signature: Signature = key.sign(&to_vec(bid).unwrap());
+    signature.to_bytes().to_vec()
+}
+
+#[test]
+fn relayed_bids() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+    let relayer = Addr::unchecked("relayer");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_contract());
+
+    let contract = BiddingContract::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        None,
+        "Bidding Contract",
+        None,
+    )
+    .unwrap();
+
+    let key = SigningKey::from_slice(&[7; 32]).unwrap();
+    let pubkey = key.verifying_key().to_encoded_point(true).as_bytes().to_vec();
+    let other_key = SigningKey::from_slice(&[8; 32]).unwrap();
+
+    let err = contract
+        .deposit(&mut app, &sender, &[1; 33], &[Coin::new(30, ATOM)])
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPubkey {});
+
+    contract
+        .deposit(&mut app, &sender, &pubkey, &[Coin::new(30, ATOM)])
+        .unwrap();
+
+    let bid = BidIntent {
+        auction: contract.addr().to_string(),
+        bidder: sender.to_string(),
+        amount: Uint128::new(10),
+        nonce: 0,
+        expiry: Expiration::AtHeight(app.block_info().height + 10),
+    };
+
+    let err = contract
+        .relay_bid(&mut app, &relayer, &bid, &sign_bid(&other_key, &bid), &pubkey)
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSignature {});
+
+    let other_pubkey = other_key.verifying_key().to_encoded_point(true).as_bytes().to_vec();
+    let err = contract
+        .relay_bid(&mut app, &relayer, &bid, &sign_bid(&other_key, &bid), &other_pubkey)
+        .unwrap_err();
+    assert_eq!(err, ContractError::PubkeyMismatch {});
+
+    let signature = sign_bid(&key, &bid);
+    contract
+        .relay_bid(&mut app, &relayer, &bid, &signature, &pubkey)
+        .unwrap();
+
+    let resp = contract.query_address_bid(&app, sender.to_string()).unwrap();
+    assert_eq!(resp.bid, Some(Coin::new(10, ATOM)));
+
+    let resp = contract.query_deposit(&app, &sender).unwrap();
+    assert_eq!(resp.balance, Coin::new(20, ATOM));
+    assert_eq!(resp.nonce, 1);
+
+    let err = contract
+        .relay_bid(&mut app, &relayer, &bid, &signature, &pubkey)
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidNonce { expected: 1 });
+
+    let expired = BidIntent {
+        nonce: 1,
+        expiry: Expiration::AtHeight(app.block_info().height),
+        ..bid.clone()
+    };
+    let err = contract
+        .relay_bid(&mut app, &relayer, &expired, &sign_bid(&key, &expired), &pubkey)
+        .unwrap_err();
+    assert_eq!(err, ContractError::BidExpired {});
+
+    let other_auction = BidIntent {
+        auction: "other".to_owned(),
+        nonce: 1,
+        ..bid.clone()
+    };
+    let err = contract
+        .relay_bid(&mut app, &relayer, &other_auction, &sign_bid(&key, &other_auction), &pubkey)
+        .unwrap_err();
+    assert_eq!(err, ContractError::WrongAuction {});
+
+    let too_high = BidIntent {
+        amount: Uint128::new(25),
+        nonce: 1,
+        ..bid
+    };
+    let err = contract
+        .relay_bid(&mut app, &relayer, &too_high, &sign_bid(&key, &too_high), &pubkey)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientDeposit {
+            available: "20atom".to_owned()
+        }
+    );
+
+    contract.withdraw_deposit(&mut app, &sender).unwrap();
+    assert_eq!(app.wrap().query_all_balances(sender.clone()).unwrap(), &[Coin::new(20, ATOM)]);
+
+    let err = contract.withdraw_deposit(&mut app, &sender).unwrap_err();
+    assert_eq!(err, ContractError::NoDeposit {});
+
+    let resp = contract.query_deposit(&app, &sender).unwrap();
+    assert_eq!(resp.nonce, 1);
+    assert_eq!(resp.pubkey.unwrap().as_slice(), pubkey.as_slice());
+}
@@ -1,7 +1,10 @@
-use cosmwasm_std::{Coin, Addr};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Coin, Addr, Binary, Decimal, Empty, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::Expiration;
 use serde::{Deserialize, Serialize};
 
+use crate::msg::{BondConfig, CommissionSchedule};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct State {
     pub address: Addr,
@@ -9,5 +12,155 @@ pub struct State {
     pub commission: Coin,
 }
 
-pub const STATE: Map<String, State> = Map::new("state");
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Config {
+    pub denom: String,
+    pub commission: Decimal,
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+    pub description: Option<String>,
+    /// Only addresses in `ALLOWLIST` can bid
+    pub allowlist_enabled: bool,
+    /// Contract answering `RegistryQueryMsg::IsEligible` for every bidder
+    pub eligibility_registry: Option<Addr>,
+    pub bond: Option<BondConfig>,
+    pub provisional_window: Option<u64>,
+    pub escrow: Option<EscrowTerms>,
+    pub pause_retract: bool,
+    /// Empty if the fees are not split
+    pub fee_split: Vec<(Addr, Decimal)>,
+    pub referral_share: Decimal,
+    pub commission_schedule: Option<CommissionSchedule>,
+    /// Commissions are only collected from the winner
+    pub refund_commissions: bool,
+    /// `start_time` or the instantiation time, the reference of time-based commissions
+    pub opened_at: Timestamp,
+}
+
+/// Validated `EscrowConfig`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EscrowTerms {
+    pub arbiter: Addr,
+    pub timeout: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingOwner {
+    pub owner: Addr,
+    pub expiry: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Settlement {
+    pub deadline: Timestamp,
+    pub confirmed: bool,
+    /// The winner's bond was slashed, or in provisional mode no bidder is left to offer the lot to
+    pub slashed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Escrow {
+    pub buyer: Addr,
+    pub amount: Uint128,
+    pub deadline: Timestamp,
+    pub disputed: bool,
+    pub released: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BidRecord {
+    pub timestamp: Timestamp,
+    pub height: u64,
+    pub amount: Coin,
+    pub commission: Coin,
+    pub total: Coin,
+}
+
+/// Commissions kept by the contract until the owner withdraws them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CollectedFees {
+    pub accrued: Uint128,
+    pub withdrawn: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Referral {
+    pub bidders: u64,
+    pub rewards: Uint128,
+}
+
+/// Transfer awaiting its outcome, credited to the recipient if it fails
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Payout {
+    pub recipient: Addr,
+    pub amount: Coin,
+}
+
+/// Funds drawn by relayed bids, signed with `pubkey`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Deposit {
+    pub pubkey: Binary,
+    pub balance: Uint128,
+    /// Nonce of the next relayed bid, kept when the deposit is withdrawn
+    pub nonce: u64,
+}
+
+pub struct BidIndexes<'a> {
+    pub amount: MultiIndex<'a, u128, State, Addr>,
+}
+
+impl<'a> IndexList<State> for BidIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<State>> + '_> {
+        let v: Vec<&dyn Index<State>> = vec![&self.amount];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn bids<'a>() -> IndexedMap<'a, &'a Addr, State, BidIndexes<'a>> {
+    let indexes = BidIndexes {
+        amount: MultiIndex::new(|_pk, state| state.bid.amount.u128(), "bids", "bids__amount"),
+    };
+    IndexedMap::new("bids", indexes)
+}
+
+/// Every accepted `Bid {}` call, keyed by bidder and a per-bidder sequence number
+pub const BID_HISTORY: Map<(&Addr, u64), BidRecord> = Map::new("bid_history");
+pub const HIGHEST_BID: Item<State> = Item::new("highest_bid");
+/// Number of entries in `bids()`, so `AllBids {}` doesn't have to count them
+pub const BIDDER_COUNT: Item<u64> = Item::new("bidder_count");
+/// Only saved when the bidding closed with a winning bid
+pub const WINNER: Item<State> = Item::new("winner");
+/// Set by `close`, whether or not anybody bid
+pub const CLOSED: Item<bool> = Item::new("closed");
+/// Missing once the owner has renounced the ownership
 pub const OWNER: Item<Addr> = Item::new("owner");
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const PAUSED: Item<bool> = Item::new("paused");
+pub const COLLECTED_FEES: Item<CollectedFees> = Item::new("collected_fees");
+/// Set by `Cancel {}`, after which every bid can be retracted and no winner is designated
+pub const CANCELLED: Item<bool> = Item::new("cancelled");
+/// Payouts awaiting their reply, keyed by their reply id
+pub const PAYOUTS: Map<u64, Payout> = Map::new("payouts");
+/// Reply id of the next payout, unique so payouts of nested executions don't collide
+pub const NEXT_PAYOUT_ID: Item<u64> = Item::new("next_payout_id");
+/// Failed payouts and referral rewards, claimable with `ClaimCredit {}`
+pub const CREDITS: Map<&Addr, Uint128> = Map::new("credits");
+/// Referrer each bidder is attributed to
+pub const REFERRERS: Map<&Addr, Addr> = Map::new("referrers");
+pub const REFERRALS: Map<&Addr, Referral> = Map::new("referrals");
+pub const DEPOSITS: Map<&Addr, Deposit> = Map::new("deposits");
+/// Contracts notified with `BiddingHookMsg`
+pub const HOOKS: Map<&Addr, Empty> = Map::new("hooks");
+/// Addresses granted a role, keyed by `Role::as_str`
+pub const ROLES: Map<(&str, &Addr), Empty> = Map::new("roles");
+pub const ALLOWLIST: Map<&Addr, Empty> = Map::new("allowlist");
+pub const DENYLIST: Map<&Addr, Empty> = Map::new("denylist");
+pub const BONDS: Map<&Addr, Uint128> = Map::new("bonds");
+/// Winner's settlement window, started by `close` when a bond is required or the winner is
+/// provisional
+pub const SETTLEMENT: Item<Settlement> = Item::new("settlement");
+/// Winning bid held until the delivery is confirmed, the dispute resolved or the timeout passes
+pub const ESCROW: Item<Escrow> = Item::new("escrow");
+/// Provisional winners who failed to confirm the purchase in time
+pub const FORFEITED: Map<&Addr, Empty> = Map::new("forfeited");